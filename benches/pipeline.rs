@@ -0,0 +1,87 @@
+use std::io;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rlox::{interpreter::Interpreter, parser::types::Value};
+
+// TODO: add a recursive `fib(25)` benchmark once functions are supported
+const COUNTING_LOOP: &str = "
+var count = 0;
+while (count < 10000) {
+    count = count + 1;
+}
+";
+
+const STRING_READS: &str = "
+var text = \"a string that is read over and over again\";
+var copy;
+var count = 0;
+while (count < 10000) {
+    copy = text;
+    count = count + 1;
+}
+";
+
+/// Builds `var result = ...;` with an expression nested `depth` parentheses deep, adding and
+/// subtracting `width` terms at every level, so that one large tree dominates the program. The
+/// result is always 1.
+fn nested_expression(depth: usize, width: usize) -> String {
+    let expression = (0..depth).fold("1".to_owned(), |inner, _| {
+        format!("({inner}){}", " + 2 - 2".repeat(width))
+    });
+
+    format!("var result = {expression};")
+}
+
+/// Runs `source` through the full lex, parse, and interpret pipeline, discarding any output so
+/// that printing does not dominate the measurement.
+fn run(source: &str) -> Interpreter {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output(io::sink());
+    rlox::run(source, &mut interpreter).expect("benchmark program should run without errors");
+
+    interpreter
+}
+
+fn counting_loop(c: &mut Criterion) {
+    // Make sure the program does what it is meant to before timing it
+    let interpreter = run(COUNTING_LOOP);
+    assert_eq!(
+        interpreter.get_global("count"),
+        Some(&Value::Number(10000.0))
+    );
+
+    c.bench_function("counting loop", |b| {
+        b.iter(|| run(black_box(COUNTING_LOOP)))
+    });
+}
+
+fn string_reads(c: &mut Criterion) {
+    let interpreter = run(STRING_READS);
+    assert_eq!(
+        interpreter.get_global("copy"),
+        interpreter.get_global("text")
+    );
+
+    c.bench_function("string reads", |b| b.iter(|| run(black_box(STRING_READS))));
+}
+
+fn large_nested_expression(c: &mut Criterion) {
+    let source = nested_expression(48, 64);
+    let interpreter = run(&source);
+    assert_eq!(interpreter.get_global("result"), Some(&Value::Number(1.0)));
+
+    c.bench_function("parse large nested expression", |b| {
+        b.iter(|| rlox::parse(black_box(&source)))
+    });
+    c.bench_function("large nested expression", |b| {
+        b.iter(|| run(black_box(&source)))
+    });
+}
+
+criterion_group!(
+    benches,
+    counting_loop,
+    string_reads,
+    large_nested_expression
+);
+criterion_main!(benches);
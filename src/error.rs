@@ -0,0 +1,136 @@
+use std::fmt::Display;
+
+use thiserror::Error;
+
+use crate::{
+    interpreter::error::RuntimeError, lexer::LexerError, parser::error::ParserError,
+    resolver::error::ResolverError,
+};
+
+/// Any error that can occur while lexing, parsing, or interpreting lox source code. Displays as
+/// the error's message prefixed with its position, if known.
+#[derive(Debug, Error, PartialEq)]
+pub enum RloxError {
+    #[error("{}", with_position(.0.position(), .0.message()))]
+    Lexer(#[from] LexerError),
+
+    #[error("{}", with_position(.0.position(), .0.to_string()))]
+    Parser(#[from] ParserError),
+
+    #[error("{}", with_position(.0.position(), .0.to_string()))]
+    Runtime(#[from] RuntimeError),
+}
+
+/// Prefixes `message` with `[line L: col C]` if `position` is known.
+fn with_position(position: Option<(usize, usize)>, message: String) -> String {
+    match position {
+        Some((line, col)) => format!("[line {line}: col {col}] {message}"),
+        None => message,
+    }
+}
+
+impl RloxError {
+    /// Returns the line and column in the source code where the error occurred, if known.
+    pub fn position(&self) -> Option<(usize, usize)> {
+        match self {
+            RloxError::Lexer(error) => error.position(),
+            RloxError::Parser(error) => error.position(),
+            RloxError::Runtime(error) => error.position(),
+        }
+    }
+
+    /// Returns the error message without the position prefix.
+    pub fn message(&self) -> String {
+        match self {
+            RloxError::Lexer(error) => error.message(),
+            RloxError::Parser(error) => error.to_string(),
+            RloxError::Runtime(error) => error.to_string(),
+        }
+    }
+}
+
+/// Every error found by an operation that keeps going after the first one, like scanning with
+/// [crate::lex_all]. Never empty.
+#[derive(Debug, PartialEq)]
+pub struct RloxErrors(pub Vec<RloxError>);
+
+impl From<RloxError> for RloxErrors {
+    fn from(error: RloxError) -> Self {
+        Self(vec![error])
+    }
+}
+
+impl From<Vec<LexerError>> for RloxErrors {
+    fn from(errors: Vec<LexerError>) -> Self {
+        Self(errors.into_iter().map(RloxError::from).collect())
+    }
+}
+
+impl Display for RloxErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, error) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{error}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for RloxErrors {}
+
+/// The stage of running lox source code that a [Diagnostic] comes from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stage {
+    Lexer,
+    Parser,
+    Resolver,
+    Runtime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A problem found in lox source code, in a form that tooling can consume without parsing
+/// rendered error messages.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub stage: Stage,
+    pub severity: Severity,
+    pub message: String,
+    /// Line and column of the problem, if known
+    pub position: Option<(usize, usize)>,
+}
+
+impl From<&RloxError> for Diagnostic {
+    fn from(error: &RloxError) -> Self {
+        let stage = match error {
+            RloxError::Lexer(_) => Stage::Lexer,
+            RloxError::Parser(_) => Stage::Parser,
+            RloxError::Runtime(_) => Stage::Runtime,
+        };
+
+        Self {
+            stage,
+            severity: Severity::Error,
+            message: error.message(),
+            position: error.position(),
+        }
+    }
+}
+
+impl From<&ResolverError> for Diagnostic {
+    fn from(warning: &ResolverError) -> Self {
+        Self {
+            stage: Stage::Resolver,
+            severity: Severity::Warning,
+            message: warning.to_string(),
+            position: warning.position(),
+        }
+    }
+}
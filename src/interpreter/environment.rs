@@ -1,4 +1,7 @@
-use std::{collections::HashMap, mem};
+use std::{
+    collections::{HashMap, HashSet},
+    mem,
+};
 
 use thiserror::Error;
 
@@ -13,6 +16,12 @@ pub struct Environment {
 
     /// All the variables contained in the current scope.
     values: HashMap<String, Value>,
+
+    /// Names of the variables in the current scope that cannot be reassigned.
+    constants: HashSet<String>,
+
+    /// Name of the current scope given by a labeled block, shown when dumping the environment.
+    label: Option<String>,
 }
 
 impl Environment {
@@ -20,6 +29,16 @@ impl Environment {
         Self {
             enclosing: None,
             values: HashMap::new(),
+            constants: HashSet::new(),
+            label: None,
+        }
+    }
+
+    /// Creates a scope named `label`, which is shown when dumping the environment.
+    pub fn new_labeled(label: String) -> Self {
+        Self {
+            label: Some(label),
+            ..Self::new()
         }
     }
 
@@ -27,6 +46,8 @@ impl Environment {
         Self {
             enclosing: Some(Box::new(enclosing)),
             values: HashMap::new(),
+            constants: HashSet::new(),
+            label: None,
         }
     }
 
@@ -46,13 +67,47 @@ impl Environment {
         Ok(())
     }
 
+    /// Creates a new constant `name` with `value` that cannot be reassigned. Returns an `Err` if
+    /// the variable already exists.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error when the variable `name` has already been defined.
+    pub fn define_const(&mut self, name: String, value: Value) -> Result<(), EnvironmentError> {
+        self.define(name.clone(), value)?;
+        self.constants.insert(name);
+
+        Ok(())
+    }
+
+    /// Creates or overwrites the variable `name` in the global scope, regardless of which scope is
+    /// currently active.
+    pub fn define_global(&mut self, name: String, value: Value) {
+        match self.enclosing {
+            Some(ref mut enclosing) => enclosing.define_global(name, value),
+            None => {
+                self.constants.remove(&name);
+                self.values.insert(name, value);
+            }
+        }
+    }
+
+    /// Gets the variable `name` from the global scope, ignoring any variables shadowing it in
+    /// the scopes currently active.
+    pub fn get_global(&self, name: &str) -> Option<&Value> {
+        match self.enclosing {
+            Some(ref enclosing) => enclosing.get_global(name),
+            None => self.values.get(name),
+        }
+    }
+
     /// Assigns `value` to an existing variable. Returns `value` if successful and an `Err` if the
     /// variable doesn't exist.
     ///
     /// # Errors
     ///
     /// This method returns an error when the variable `name` has not been defined in the current scope
-    /// or any of its enclosing scopes.
+    /// or any of its enclosing scopes, or when it is a constant.
     pub fn assign(&mut self, name: String, value: Value) -> Result<(), EnvironmentError> {
         fn assign_recur(
             env: &mut dyn AsMut<Environment>,
@@ -69,6 +124,10 @@ impl Environment {
                 }
             }
 
+            if env.constants.contains(&name) {
+                return Err(EnvironmentError::AssignToConst(name));
+            }
+
             env.values.insert(name, value);
             Ok(())
         }
@@ -105,15 +164,57 @@ impl Environment {
         get_recur(self, name)
     }
 
+    /// Returns the number of scopes enclosing the current scope. The global scope has a depth of
+    /// 0.
+    pub fn depth(&self) -> usize {
+        match self.enclosing {
+            Some(ref enclosing) => enclosing.depth() + 1,
+            None => 0,
+        }
+    }
+
+    /// Lists the variables of every scope, starting from the current scope and ending with the
+    /// global scope. Variables within a scope are sorted by name.
+    pub fn dump(&self) -> String {
+        let mut dump = String::new();
+        let mut env = Some(self);
+
+        while let Some(scope) = env {
+            let depth = scope.depth();
+            match (depth, &scope.label) {
+                (0, _) => dump.push_str("scope 0 (global):\n"),
+                (_, Some(label)) => dump.push_str(&format!("scope {depth} ({label}):\n")),
+                (_, None) => dump.push_str(&format!("scope {depth}:\n")),
+            }
+
+            let mut names = scope.values.keys().collect::<Vec<&String>>();
+            names.sort();
+            for name in names {
+                dump.push_str(&format!("  {name} = {}\n", scope.values[name]));
+            }
+
+            env = scope.enclosing.as_deref();
+        }
+
+        dump
+    }
+
     /// Creates a new scope by replacing the current `self` with a new `Environment` scope and
     /// setting the current `self` as the `enclosing` of the new scope.
     pub fn enter_new_scope(&mut self) {
-        // This works because `mem::take` replaces the original value with its default
-        // values which is what we want with a new scope.
-        let enclosing = mem::take(self);
+        self.enter_scope(Environment::new());
+    }
+
+    /// Like [Environment::enter_new_scope], but names the new scope `label`.
+    pub fn enter_new_labeled_scope(&mut self, label: String) {
+        self.enter_scope(Environment::new_labeled(label));
+    }
 
-        // Set the "current" scope as the enclosing of the newly created scope
-        self.enclosing = Some(Box::new(enclosing));
+    /// Makes `scope` the current scope and sets the current `self` as its `enclosing` scope,
+    /// replacing any enclosing scope `scope` already had.
+    pub fn enter_scope(&mut self, mut scope: Environment) {
+        scope.enclosing = Some(Box::new(mem::replace(self, Environment::new())));
+        *self = scope;
     }
 
     /// Exits the current scope, sets its enclosing scope as the current scope, and returns the
@@ -144,6 +245,9 @@ pub enum EnvironmentError {
     #[error("undefined variable '{}'", .0)]
     UndefinedVariable(String),
 
+    #[error("cannot assign to constant '{}'", .0)]
+    AssignToConst(String),
+
     #[error("cannot exit the global scope")]
     ExitingGlobalScope,
 }
@@ -159,3 +263,58 @@ impl AsMut<Environment> for Environment {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_counts_enclosing_scopes() {
+        let mut env = Environment::new();
+        assert_eq!(env.depth(), 0);
+
+        env.enter_new_scope();
+        env.enter_new_scope();
+        assert_eq!(env.depth(), 2);
+
+        env.exit_current_scope().unwrap();
+        assert_eq!(env.depth(), 1);
+    }
+
+    #[test]
+    fn dump_lists_variables_per_scope() {
+        let mut env = Environment::new();
+        env.define("b".to_owned(), Value::Number(2.0)).unwrap();
+        env.define("a".to_owned(), Value::Number(1.0)).unwrap();
+        env.enter_new_scope();
+        env.define("c".to_owned(), Value::String("three".into()))
+            .unwrap();
+
+        let expected = "scope 1:\n  c = \"three\"\nscope 0 (global):\n  a = 1\n  b = 2\n";
+
+        assert_eq!(env.dump(), expected);
+    }
+
+    #[test]
+    fn dump_shows_scope_labels() {
+        let mut env = Environment::new();
+        env.enter_new_labeled_scope("setup".to_owned());
+        env.define("a".to_owned(), Value::Number(1.0)).unwrap();
+        env.enter_new_scope();
+
+        let expected = "scope 2:\nscope 1 (setup):\n  a = 1\nscope 0 (global):\n";
+
+        assert_eq!(env.dump(), expected);
+    }
+
+    #[test]
+    fn get_global_skips_shadowing_scopes() {
+        let mut env = Environment::new();
+        env.define("a".to_owned(), Value::Number(1.0)).unwrap();
+        env.enter_new_scope();
+        env.define("a".to_owned(), Value::Number(2.0)).unwrap();
+
+        assert_eq!(env.get_global("a"), Some(&Value::Number(1.0)));
+        assert_eq!(env.get_global("b"), None);
+    }
+}
@@ -1,3 +1,5 @@
+use std::io;
+
 use thiserror::Error;
 
 use crate::{
@@ -9,7 +11,7 @@ use super::environment::EnvironmentError;
 
 pub type Result<T> = std::result::Result<T, RuntimeError>;
 
-#[derive(Debug, Error, PartialEq)]
+#[derive(Debug, Clone, Error, PartialEq)]
 pub enum RuntimeError {
     #[error("variable '{}' is already defined.", .name)]
     VariableAlreadyDefined {
@@ -31,23 +33,167 @@ pub enum RuntimeError {
         col: usize,
     },
 
+    #[error("cannot assign to constant '{}'", .name)]
+    AssignToConst {
+        /// Name of the constant
+        name: String,
+        /// Line of the assignment
+        line: usize,
+        /// Column of the assignment
+        col: usize,
+    },
+
     #[error("cannot assign a value to {}", .0.token_type.name())]
     InvalidAssignTarget(Token),
 
+    #[error("{} is not a valid variable name", .0.token_type.name())]
+    InvalidVariableName(Token),
+
     #[error("invalid operands for '{}', expected {}", .operator, .expected)]
     InvalidOperands {
         operator: Operator,
         expected: String,
     },
 
+    #[error("operand of '{}' is nil, is a variable uninitialized?", .operator)]
+    NilOperand { operator: Operator },
+
     #[error("invalid operator '{}'", .0)]
     InvalidUnaryOperator(Operator),
 
+    #[error("invalid binary operator '{}'", .0)]
+    InvalidBinaryOperator(Operator),
+
+    #[error("invalid logical operator '{}'", .0)]
+    InvalidLogicalOperator(Operator),
+
     #[error("invalid operator '{}' for value {}", .operator, .value)]
     InvalidUnaryOperatorForValue { operator: Operator, value: Value },
+
+    #[error("unsupported operator '{}'", .0)]
+    UnsupportedOperator(Operator),
+
+    #[error("can only call functions, got a value of type {}", .type_name)]
+    CallNonCallable {
+        /// Type of the value that was called, as returned by [Value::type_name]
+        type_name: &'static str,
+        /// Line of the call's closing parenthesis
+        line: usize,
+        /// Column of the call's closing parenthesis
+        col: usize,
+    },
+
+    #[error("can only index strings, got a value of type {}", .type_name)]
+    IndexNonString {
+        /// Type of the value that was indexed, as returned by [Value::type_name]
+        type_name: &'static str,
+        /// Line of the index's closing bracket
+        line: usize,
+        /// Column of the index's closing bracket
+        col: usize,
+    },
+
+    #[error("index must be a non-negative integer, got {}", .index)]
+    InvalidIndex {
+        index: Value,
+        /// Line of the index's closing bracket
+        line: usize,
+        /// Column of the index's closing bracket
+        col: usize,
+    },
+
+    #[error("index {} is out of range for a string of {} characters", .index, .len)]
+    IndexOutOfRange {
+        index: f64,
+        /// Number of characters in the indexed string
+        len: usize,
+        /// Line of the index's closing bracket
+        line: usize,
+        /// Column of the index's closing bracket
+        col: usize,
+    },
+
+    #[error("expected {} arguments but got {}", .expected, .got)]
+    ArityMismatch {
+        expected: usize,
+        got: usize,
+        /// Line of the call's closing parenthesis
+        line: usize,
+        /// Column of the call's closing parenthesis
+        col: usize,
+    },
+
+    #[error("expected {}, got {}", .expected, .got)]
+    TypeMismatch {
+        /// Description of the expected type
+        expected: String,
+        got: Value,
+    },
+
+    #[error("exceeded the limit of {} loop iterations", .limit)]
+    LoopLimitExceeded { limit: usize },
+
+    #[error("exceeded the limit of {} execution steps", .limit)]
+    StepLimitExceeded { limit: usize },
+
+    /// Writing the output of a `print` statement failed, like when stdout is a closed pipe.
+    #[error("failed to write output: {}", .message)]
+    Output {
+        kind: io::ErrorKind,
+        message: String,
+    },
+
+    #[error("{}: {}", .name, .message)]
+    NativeFunctionError {
+        /// Name of the native function
+        name: String,
+        message: String,
+        /// Line of the call's closing parenthesis
+        line: usize,
+        /// Column of the call's closing parenthesis
+        col: usize,
+    },
 }
 
+/// A float epsilon rejected by [Interpreter::set_float_epsilon] for being negative or NaN
+///
+/// [Interpreter::set_float_epsilon]: super::Interpreter::set_float_epsilon
+#[derive(Debug, Clone, Copy, Error, PartialEq)]
+#[error("float epsilon must be a non-negative number, got {0}")]
+pub struct InvalidFloatEpsilon(pub f64);
+
 impl RuntimeError {
+    /// Returns the line and column in the source code where the error occurred, if known.
+    pub fn position(&self) -> Option<(usize, usize)> {
+        match self {
+            RuntimeError::VariableAlreadyDefined { line, col, .. }
+            | RuntimeError::UndefinedVariable { line, col, .. }
+            | RuntimeError::AssignToConst { line, col, .. }
+            | RuntimeError::CallNonCallable { line, col, .. }
+            | RuntimeError::ArityMismatch { line, col, .. }
+            | RuntimeError::IndexNonString { line, col, .. }
+            | RuntimeError::InvalidIndex { line, col, .. }
+            | RuntimeError::IndexOutOfRange { line, col, .. }
+            | RuntimeError::NativeFunctionError { line, col, .. } => Some((*line, *col)),
+            RuntimeError::InvalidAssignTarget(token) | RuntimeError::InvalidVariableName(token) => {
+                Some((token.line, token.col))
+            }
+            RuntimeError::InvalidOperands { operator, .. }
+            | RuntimeError::NilOperand { operator }
+            | RuntimeError::InvalidUnaryOperator(operator)
+            | RuntimeError::InvalidBinaryOperator(operator)
+            | RuntimeError::InvalidLogicalOperator(operator)
+            | RuntimeError::InvalidUnaryOperatorForValue { operator, .. }
+            | RuntimeError::UnsupportedOperator(operator) => {
+                Some((operator.src_line, operator.src_col))
+            }
+            RuntimeError::TypeMismatch { .. }
+            | RuntimeError::LoopLimitExceeded { .. }
+            | RuntimeError::StepLimitExceeded { .. }
+            | RuntimeError::Output { .. } => None,
+        }
+    }
+
     pub fn from_env_err(env_err: EnvironmentError, name_token: Token) -> Self {
         match env_err {
             EnvironmentError::VariableAlreadyDefined(name) => Self::VariableAlreadyDefined {
@@ -60,9 +206,59 @@ impl RuntimeError {
                 line: name_token.line,
                 col: name_token.col,
             },
+            EnvironmentError::AssignToConst(name) => Self::AssignToConst {
+                name,
+                line: name_token.line,
+                col: name_token.col,
+            },
             EnvironmentError::ExitingGlobalScope => {
                 panic!("The interpreter should never try to exit the global scope.")
             }
         }
     }
 }
+
+impl From<io::Error> for RuntimeError {
+    fn from(error: io::Error) -> Self {
+        Self::Output {
+            kind: error.kind(),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl TryFrom<Value> for f64 {
+    type Error = RuntimeError;
+
+    fn try_from(value: Value) -> Result<Self> {
+        value.as_number().ok_or_else(|| RuntimeError::TypeMismatch {
+            expected: "a number".to_owned(),
+            got: value,
+        })
+    }
+}
+
+impl TryFrom<Value> for bool {
+    type Error = RuntimeError;
+
+    fn try_from(value: Value) -> Result<Self> {
+        value.as_bool().ok_or_else(|| RuntimeError::TypeMismatch {
+            expected: "a boolean".to_owned(),
+            got: value,
+        })
+    }
+}
+
+impl TryFrom<Value> for String {
+    type Error = RuntimeError;
+
+    fn try_from(value: Value) -> Result<Self> {
+        match value {
+            Value::String(string) => Ok(string.to_string()),
+            got => Err(RuntimeError::TypeMismatch {
+                expected: "a string".to_owned(),
+                got,
+            }),
+        }
+    }
+}
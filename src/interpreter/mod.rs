@@ -1,21 +1,72 @@
+use std::{
+    collections::HashMap,
+    env,
+    io::{self, Write},
+};
+
 use crate::{
-    lexer::token::TokenType,
-    parser::types::{Expr, Operator, OperatorType, Stmt, Value},
+    lexer::token::{Token, TokenType},
+    parser::types::{ExprNode, ExprRef, NumberFormat, Operator, OperatorType, Stmt, Value},
 };
 
 use self::environment::Environment;
-use error::{Result, RuntimeError};
+use error::{InvalidFloatEpsilon, Result, RuntimeError};
 
 pub mod environment;
 pub mod error;
+pub mod natives;
 
 pub trait ErrorReporter {
     fn report_err(&self, error: &RuntimeError);
 }
 
+/// Why execution of a statement stopped early. Breaks unwind to their loop this way without
+/// being exposed as errors.
+enum Unwind {
+    /// A `break`, with its label if it has one
+    Break(Option<String>),
+    Error(RuntimeError),
+}
+
+impl From<RuntimeError> for Unwind {
+    fn from(error: RuntimeError) -> Self {
+        Unwind::Error(error)
+    }
+}
+
+/// Called with each statement before it is executed, see [Interpreter::set_trace_hook]
+pub type StmtHook = Box<dyn FnMut(&Stmt)>;
+
+/// Called with each expression before it is evaluated, see [Interpreter::set_expr_trace_hook]
+pub type ExprHook = Box<dyn FnMut(ExprRef<'_>)>;
+
 pub struct Interpreter {
     environment: Environment,
+    /// Native functions, looked up when a variable is not defined in any scope so that user
+    /// declarations shadow them.
+    natives: HashMap<String, Value>,
     error_reporters: Vec<Box<dyn ErrorReporter>>,
+    /// Where `print` statements write to. Defaults to stdout.
+    output: Box<dyn Write>,
+    /// How `print` statements format numbers
+    number_format: NumberFormat,
+    /// The largest difference at which `==` and `!=` consider two numbers equal, or `None` to
+    /// compare numbers exactly
+    float_epsilon: Option<f64>,
+    /// The maximum number of loop iterations allowed in total, or `None` if unlimited
+    max_loop_iterations: Option<usize>,
+    /// Loop iterations run so far, counted against `max_loop_iterations`
+    loop_iterations: usize,
+    /// The maximum number of statements and expressions executed in total, or `None` if unlimited
+    max_steps: Option<usize>,
+    /// Statements and expressions executed so far, counted against `max_steps`
+    steps: usize,
+    /// Trace hooks for debuggers and profilers, both `None` by default
+    stmt_hook: Option<StmtHook>,
+    expr_hook: Option<ExprHook>,
+    /// Logs the environment to stderr when exiting a scope. Enabled by setting the `RLOX_TRACE`
+    /// environment variable.
+    trace: bool,
 }
 
 impl Default for Interpreter {
@@ -26,22 +77,125 @@ impl Default for Interpreter {
 
 impl Interpreter {
     pub fn new() -> Self {
-        Self {
-            environment: Environment::default(),
-            error_reporters: Vec::new(),
-        }
+        Self::with_reporters([])
     }
 
     pub fn with_reporters<I>(reporters: I) -> Self
     where
         I: IntoIterator<Item = Box<dyn ErrorReporter>>,
     {
+        let natives = natives::all()
+            .into_iter()
+            .map(|native| (native.name.to_owned(), Value::NativeFunction(native)))
+            .collect();
+
         Self {
             environment: Environment::default(),
+            natives,
             error_reporters: reporters.into_iter().collect(),
+            output: Box::new(io::stdout()),
+            number_format: NumberFormat::default(),
+            float_epsilon: None,
+            max_loop_iterations: None,
+            loop_iterations: 0,
+            max_steps: None,
+            steps: 0,
+            stmt_hook: None,
+            expr_hook: None,
+            trace: env::var_os("RLOX_TRACE").is_some(),
         }
     }
 
+    /// Redirects the output of `print` statements to `output`.
+    pub fn set_output<W: Write + 'static>(&mut self, output: W) {
+        self.output = Box::new(output);
+    }
+
+    /// Discards all variables defined so far. Natives, output, and other settings are kept.
+    pub fn reset(&mut self) {
+        self.environment = Environment::default();
+    }
+
+    /// Lists the variables in every active scope. See [Environment::dump] for the format.
+    pub fn dump_environment(&self) -> String {
+        self.environment.dump()
+    }
+
+    /// Limits the total number of loop iterations, across all loops, that scripts can run before
+    /// failing with [RuntimeError::LoopLimitExceeded]. Useful for running untrusted scripts that
+    /// may never terminate. `None` removes the limit, which is the default. Resets the iterations
+    /// counted so far.
+    pub fn set_max_loop_iterations(&mut self, max_loop_iterations: Option<usize>) {
+        self.max_loop_iterations = max_loop_iterations;
+        self.loop_iterations = 0;
+    }
+
+    /// Limits the total number of statements and expressions that scripts can execute before
+    /// failing with [RuntimeError::StepLimitExceeded]. `None` removes the limit, which is the
+    /// default. Resets the steps counted so far.
+    pub fn set_max_steps(&mut self, max_steps: Option<usize>) {
+        self.max_steps = max_steps;
+        self.steps = 0;
+    }
+
+    /// Installs a hook called with every statement right before it is executed, for observing
+    /// execution from debuggers and profilers. `None` removes the hook, which is the default.
+    pub fn set_trace_hook(&mut self, hook: Option<StmtHook>) {
+        self.stmt_hook = hook;
+    }
+
+    /// Like [Interpreter::set_trace_hook], but the hook is called with every expression right
+    /// before it is evaluated.
+    pub fn set_expr_trace_hook(&mut self, hook: Option<ExprHook>) {
+        self.expr_hook = hook;
+    }
+
+    /// Counts one step against `max_steps`.
+    fn step(&mut self) -> Result<()> {
+        self.steps += 1;
+        match self.max_steps {
+            Some(limit) if self.steps > limit => Err(RuntimeError::StepLimitExceeded { limit }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Sets how `print` statements format numbers.
+    pub fn set_number_format(&mut self, number_format: NumberFormat) {
+        self.number_format = number_format;
+    }
+
+    /// Makes `==` and `!=` treat two numbers as equal when they differ by at most `float_epsilon`,
+    /// so that `0.1 + 0.2 == 0.3` holds. `None` compares numbers exactly, which is the default.
+    ///
+    /// # Errors
+    ///
+    /// Returns [InvalidFloatEpsilon] and keeps the current epsilon if `float_epsilon` is negative
+    /// or NaN.
+    pub fn set_float_epsilon(
+        &mut self,
+        float_epsilon: Option<f64>,
+    ) -> std::result::Result<(), InvalidFloatEpsilon> {
+        match float_epsilon {
+            Some(epsilon) if epsilon.is_nan() || epsilon < 0.0 => Err(InvalidFloatEpsilon(epsilon)),
+            _ => {
+                self.float_epsilon = float_epsilon;
+                Ok(())
+            }
+        }
+    }
+
+    /// Defines the global variable `name` with `value`, overwriting it if it already exists.
+    /// Useful for injecting host values into the interpreter before running a script.
+    pub fn define_global(&mut self, name: impl Into<String>, value: impl Into<Value>) {
+        self.environment.define_global(name.into(), value.into());
+    }
+
+    /// Gets the global variable `name`. Useful for reading results back out of the interpreter
+    /// after running a script.
+    pub fn get_global(&self, name: &str) -> Option<&Value> {
+        self.environment.get_global(name)
+    }
+
     pub fn interpret(&mut self, statements: Vec<Stmt>) {
         for stmt in statements {
             if let Err(e) = self.execute(&stmt) {
@@ -52,45 +206,118 @@ impl Interpreter {
         }
     }
 
-    pub fn execute(&mut self, stmt: &Stmt) -> Result<()> {
-        match stmt {
-            Stmt::Block(stmts) => {
-                self.environment.enter_new_scope();
+    /// Interprets `statements` like [Interpreter::interpret], but returns every error encountered
+    /// instead of passing them to the error reporters. A failing statement does not stop the
+    /// statements after it from running.
+    pub fn interpret_collect(&mut self, statements: Vec<Stmt>) -> Vec<RuntimeError> {
+        statements
+            .iter()
+            .filter_map(|stmt| self.execute(stmt).err())
+            .collect()
+    }
 
-                for stmt in stmts {
-                    self.execute(stmt)?;
+    /// Interprets `statements` entered in a REPL. Works like [Interpreter::interpret] except that
+    /// the values of bare expression statements are also printed, unless the expression is an
+    /// assignment or evaluates to `nil`.
+    pub fn interpret_repl(&mut self, statements: Vec<Stmt>) {
+        for stmt in statements {
+            let is_assignment = match &stmt {
+                Stmt::Expression(expr) => matches!(expr.root().node(), ExprNode::Assign { .. }),
+                _ => false,
+            };
+
+            let result = self.execute_for_value(&stmt).and_then(|value| match value {
+                None | Some(Value::Nil) => Ok(()),
+                Some(_) if is_assignment => Ok(()),
+                Some(value) => self.print(&value),
+            });
+
+            if let Err(e) = result {
+                self.error_reporters
+                    .iter()
+                    .for_each(|reporter| reporter.report_err(&e))
+            }
+        }
+    }
+
+    /// Executes `stmt` like [Interpreter::execute], returning the value of the expression if
+    /// `stmt` is an expression statement.
+    pub fn execute_for_value(&mut self, stmt: &Stmt) -> Result<Option<Value>> {
+        match stmt {
+            Stmt::Expression(expr) => {
+                self.step()?;
+                if let Some(hook) = &mut self.stmt_hook {
+                    hook(stmt);
                 }
+                self.evaluate(expr.root()).map(Some)
+            }
+            _ => self.execute(stmt).map(|_| None),
+        }
+    }
 
-                self.environment
-                    .exit_current_scope()
-                    .expect("should never fail to exit a newly entered scope");
+    /// Executes `stmt`. A `break` outside of any loop, which the parser never produces, stops
+    /// executing the statement it is in.
+    pub fn execute(&mut self, stmt: &Stmt) -> Result<()> {
+        match self.execute_unwinding(stmt) {
+            Ok(()) | Err(Unwind::Break(_)) => Ok(()),
+            Err(Unwind::Error(error)) => Err(error),
+        }
+    }
+
+    fn execute_unwinding(&mut self, stmt: &Stmt) -> std::result::Result<(), Unwind> {
+        self.step()?;
+        if let Some(hook) = &mut self.stmt_hook {
+            hook(stmt);
+        }
+
+        match stmt {
+            Stmt::Block(stmts) => {
+                self.execute_block_unwinding(stmts, Environment::new())?;
             }
+            Stmt::Break { label, .. } => return Err(Unwind::Break(label.clone())),
             Stmt::Expression(expr) => {
-                self.evaluate(expr)?;
+                self.evaluate(expr.root())?;
             }
             Stmt::If {
                 condition,
                 then_branch: then_body,
                 else_branch: else_body,
             } => {
-                if self.evaluate(condition)?.is_truthy() {
-                    self.execute(then_body)?;
+                if self.evaluate(condition.root())?.is_truthy() {
+                    self.execute_unwinding(then_body)?;
                 } else if let Some(else_body) = else_body {
-                    self.execute(else_body)?;
+                    self.execute_unwinding(else_body)?;
                 }
             }
-            Stmt::Print(expr) => println!("{}", self.evaluate(expr)?.stringify()),
+            Stmt::Print(expr) => {
+                let value = self.evaluate(expr.root())?;
+                self.print(&value)?;
+            }
+            Stmt::Const {
+                name: name_token,
+                initializer,
+            } => {
+                let TokenType::Identifier(name) = name_token.clone().token_type else {
+                    return Err(RuntimeError::InvalidVariableName(name_token.clone()).into());
+                };
+
+                let value = self.evaluate(initializer.root())?;
+
+                self.environment
+                    .define_const(name, value)
+                    .map_err(|env_err| RuntimeError::from_env_err(env_err, name_token.clone()))?;
+            }
             Stmt::Var {
                 name: name_token,
                 initializer,
             } => {
                 let TokenType::Identifier(name) = name_token.clone().token_type else {
-                    panic!("`name` field in `Stmt::Var` should always be an identifier");
+                    return Err(RuntimeError::InvalidVariableName(name_token.clone()).into());
                 };
 
                 let initial_value = initializer
                     .as_ref()
-                    .map(|expr| self.evaluate(expr))
+                    .map(|expr| self.evaluate(expr.root()))
                     .transpose()?
                     .unwrap_or(Value::Nil); // Uninitialized variables default to `nil`
 
@@ -98,9 +325,34 @@ impl Interpreter {
                     .define(name, initial_value)
                     .map_err(|env_err| RuntimeError::from_env_err(env_err, name_token.clone()))?;
             }
+            Stmt::Labeled { label, body } => {
+                let result = match body.as_ref() {
+                    // Labeled blocks, including desugared `for` loops, name their scope
+                    Stmt::Block(stmts) => {
+                        self.execute_block_unwinding(stmts, Environment::new_labeled(label.clone()))
+                    }
+                    body => self.execute_unwinding(body),
+                };
+
+                match result {
+                    Err(Unwind::Break(Some(ref target))) if target == label => {}
+                    result => result?,
+                }
+            }
             Stmt::While { condition, body } => {
-                while self.evaluate(condition)?.is_truthy() {
-                    self.execute(body)?;
+                while self.evaluate(condition.root())?.is_truthy() {
+                    self.loop_iterations += 1;
+                    if let Some(limit) = self.max_loop_iterations {
+                        if self.loop_iterations > limit {
+                            return Err(RuntimeError::LoopLimitExceeded { limit }.into());
+                        }
+                    }
+
+                    match self.execute_unwinding(body) {
+                        // Labeled breaks are handled by the matching `Stmt::Labeled`
+                        Err(Unwind::Break(None)) => break,
+                        result => result?,
+                    }
                 }
             }
         }
@@ -108,9 +360,57 @@ impl Interpreter {
         Ok(())
     }
 
-    fn evaluate(&mut self, expr: &Expr) -> Result<Value> {
-        let value = match expr {
-            Expr::Assign {
+    /// Executes `stmts` with `scope` as the current scope, enclosed by the scope that was current
+    /// before. Variables already defined in `scope` are visible to `stmts`. The previous scope is
+    /// restored afterwards, even if a statement fails.
+    pub fn execute_block(&mut self, stmts: &[Stmt], scope: Environment) -> Result<()> {
+        match self.execute_block_unwinding(stmts, scope) {
+            Ok(()) | Err(Unwind::Break(_)) => Ok(()),
+            Err(Unwind::Error(error)) => Err(error),
+        }
+    }
+
+    fn execute_block_unwinding(
+        &mut self,
+        stmts: &[Stmt],
+        scope: Environment,
+    ) -> std::result::Result<(), Unwind> {
+        self.environment.enter_scope(scope);
+
+        // Exit the scope before propagating any error so it is never leaked
+        let result = stmts
+            .iter()
+            .try_for_each(|stmt| self.execute_unwinding(stmt));
+
+        if self.trace {
+            eprintln!(
+                "[trace] exiting scope at depth {}\n{}",
+                self.environment.depth(),
+                self.environment.dump()
+            );
+        }
+
+        // Cannot fail, the scope entered above is never the global scope
+        self.environment
+            .exit_current_scope()
+            .expect("should never fail to exit a newly entered scope");
+
+        result
+    }
+
+    fn print(&mut self, value: &Value) -> Result<()> {
+        writeln!(self.output, "{}", value.stringify_with(self.number_format))
+            .map_err(RuntimeError::from)
+    }
+
+    fn evaluate(&mut self, expr: ExprRef<'_>) -> Result<Value> {
+        self.step()?;
+        if let Some(hook) = &mut self.expr_hook {
+            hook(expr);
+        }
+
+        let value = match expr.node() {
+            ExprNode::Assign {
                 name: name_token,
                 value,
             } => {
@@ -122,46 +422,180 @@ impl Interpreter {
                     name
                 };
 
-                let value = self.evaluate(value)?;
+                let value = self.evaluate(expr.child(*value))?;
                 self.environment
                     .assign(name.to_owned(), value.clone())
                     .map_err(|env_err| RuntimeError::from_env_err(env_err, name_token.clone()))?;
 
                 value
             }
-            Expr::Binary {
+            ExprNode::Binary {
                 left,
                 operator,
                 right,
             } => {
-                let left = self.evaluate(left)?;
-                let right = self.evaluate(right)?;
+                let left = self.evaluate(expr.child(*left))?;
+                let right = self.evaluate(expr.child(*right))?;
 
                 self.evaluate_binary_expression(left, right, operator.clone())?
             }
-            Expr::Grouping { inner } => self.evaluate(inner)?,
-            Expr::Literal { value } => value.clone(),
-            Expr::Unary { operator, right } => self.evaluate_unary_expression(operator, right)?,
-            Expr::Variable { name: name_token } => {
+            ExprNode::Grouping { inner } => self.evaluate(expr.child(*inner))?,
+            ExprNode::Logical {
+                left,
+                operator,
+                right,
+            } => {
+                let left = self.evaluate(expr.child(*left))?;
+
+                // Both `and` and `or` return one of their operands rather than a boolean, and
+                // only evaluate the right operand if the left one doesn't decide the result
+                match operator.operator_type {
+                    OperatorType::And => {
+                        if left.is_truthy() {
+                            self.evaluate(expr.child(*right))?
+                        } else {
+                            left
+                        }
+                    }
+                    OperatorType::Or => {
+                        if left.is_truthy() {
+                            left
+                        } else {
+                            self.evaluate(expr.child(*right))?
+                        }
+                    }
+                    OperatorType::QuestionQuestion => {
+                        if left == Value::Nil {
+                            self.evaluate(expr.child(*right))?
+                        } else {
+                            left
+                        }
+                    }
+                    _ => return Err(RuntimeError::InvalidLogicalOperator(operator.clone())),
+                }
+            }
+            ExprNode::Literal { value } => value.clone(),
+            ExprNode::Unary { operator, right } => {
+                self.evaluate_unary_expression(operator, expr.child(*right))?
+            }
+            ExprNode::Variable { name: name_token } => {
                 let name = {
                     let TokenType::Identifier(ref name) = name_token.token_type else {
-                        panic!("name token for `Expr::Variable` should always be an identifier");
+                        return Err(RuntimeError::InvalidVariableName(name_token.clone()));
                     };
 
                     name
                 };
 
-                self.environment
-                    .get(name)
-                    .cloned()
-                    .map_err(|env_err| RuntimeError::from_env_err(env_err, name_token.clone()))?
+                self.environment.get(name).cloned().or_else(|env_err| {
+                    // Fall back to native functions only after all scopes are searched
+                    self.natives
+                        .get(name)
+                        .cloned()
+                        .ok_or_else(|| RuntimeError::from_env_err(env_err, name_token.clone()))
+                })?
+            }
+            ExprNode::Call {
+                callee,
+                paren,
+                arguments,
+            } => {
+                let callee = self.evaluate(expr.child(*callee))?;
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| self.evaluate(expr.child(*argument)))
+                    .collect::<Result<Vec<Value>>>()?;
+
+                self.call(callee, arguments, paren)?
+            }
+            ExprNode::Index {
+                object,
+                bracket,
+                index,
+            } => {
+                let object = self.evaluate(expr.child(*object))?;
+                let index = self.evaluate(expr.child(*index))?;
+
+                self.index(object, index, bracket)?
             }
         };
 
         Ok(value)
     }
 
-    fn evaluate_unary_expression(&mut self, operator: &Operator, rhs: &Expr) -> Result<Value> {
+    fn call(&self, callee: Value, arguments: Vec<Value>, paren: &Token) -> Result<Value> {
+        if !callee.is_callable() {
+            return Err(RuntimeError::CallNonCallable {
+                type_name: callee.type_name(),
+                line: paren.line,
+                col: paren.col,
+            });
+        }
+
+        if let Some(arity) = callee.arity() {
+            if arguments.len() != arity {
+                return Err(RuntimeError::ArityMismatch {
+                    expected: arity,
+                    got: arguments.len(),
+                    line: paren.line,
+                    col: paren.col,
+                });
+            }
+        }
+
+        // Cannot fail as long as `is_callable` is kept in sync with the callable variants
+        let Value::NativeFunction(native) = callee else {
+            unreachable!("native functions are the only callable values");
+        };
+
+        (native.function)(&arguments).map_err(|message| RuntimeError::NativeFunctionError {
+            name: native.name.to_owned(),
+            message,
+            line: paren.line,
+            col: paren.col,
+        })
+    }
+
+    /// Returns the character at `index` of the string `object` as a one-character string. Indices
+    /// count characters rather than bytes, so multi-byte characters are never split.
+    fn index(&self, object: Value, index: Value, bracket: &Token) -> Result<Value> {
+        let Value::String(string) = object else {
+            return Err(RuntimeError::IndexNonString {
+                type_name: object.type_name(),
+                line: bracket.line,
+                col: bracket.col,
+            });
+        };
+
+        let Some(position) = index
+            .as_number()
+            .filter(|number| *number >= 0.0 && number.fract() == 0.0)
+        else {
+            return Err(RuntimeError::InvalidIndex {
+                index,
+                line: bracket.line,
+                col: bracket.col,
+            });
+        };
+
+        // Indices too large for `usize` saturate, which is out of range all the same
+        string
+            .chars()
+            .nth(position as usize)
+            .map(|c| Value::String(c.to_string().into()))
+            .ok_or_else(|| RuntimeError::IndexOutOfRange {
+                index: position,
+                len: string.chars().count(),
+                line: bracket.line,
+                col: bracket.col,
+            })
+    }
+
+    fn evaluate_unary_expression(
+        &mut self,
+        operator: &Operator,
+        rhs: ExprRef<'_>,
+    ) -> Result<Value> {
         match operator.operator_type {
             OperatorType::Minus => {
                 let rhs = self.evaluate(rhs)?;
@@ -175,6 +609,19 @@ impl Interpreter {
                     })
                 }
             }
+            // Identity, only accepted for numbers like its negation
+            OperatorType::Plus => {
+                let rhs = self.evaluate(rhs)?;
+
+                if let Value::Number(number) = rhs {
+                    Ok(Value::Number(number))
+                } else {
+                    Err(RuntimeError::InvalidUnaryOperatorForValue {
+                        operator: operator.clone(),
+                        value: rhs,
+                    })
+                }
+            }
             OperatorType::Bang => {
                 let rhs = self.evaluate(rhs)?;
 
@@ -193,12 +640,38 @@ impl Interpreter {
         }
     }
 
+    /// Compares two values for `==` and `!=`, comparing numbers within `float_epsilon` if set.
+    fn values_equal(&self, left: &Value, right: &Value) -> bool {
+        match (left, right, self.float_epsilon) {
+            (Value::Number(lhs), Value::Number(rhs), Some(epsilon)) => {
+                lhs == rhs || (lhs - rhs).abs() <= epsilon
+            }
+            _ => left == right,
+        }
+    }
+
     fn evaluate_binary_expression(
         &self,
         left: Value,
         right: Value,
         operator: Operator,
     ) -> Result<Value> {
+        // A `nil` operand usually means an uninitialized variable, so call that out specifically
+        let is_numeric_operator = matches!(
+            operator.operator_type,
+            OperatorType::Minus
+                | OperatorType::Percent
+                | OperatorType::Slash
+                | OperatorType::Star
+                | OperatorType::Greater
+                | OperatorType::GreaterEqual
+                | OperatorType::Less
+                | OperatorType::LessEqual
+        );
+        if is_numeric_operator && (left == Value::Nil || right == Value::Nil) {
+            return Err(RuntimeError::NilOperand { operator });
+        }
+
         let value = match operator.operator_type {
             OperatorType::Minus => match (left, right) {
                 (Value::Number(lhs), Value::Number(rhs)) => Value::Number(lhs - rhs),
@@ -212,11 +685,29 @@ impl Interpreter {
             OperatorType::Plus => match (left, right) {
                 (Value::Number(lhs), Value::Number(rhs)) => Value::Number(lhs + rhs),
 
+                // Functions are never implicitly converted to strings
+                (Value::NativeFunction(_), _) | (_, Value::NativeFunction(_)) => {
+                    return Err(RuntimeError::InvalidOperands {
+                        operator,
+                        expected: "two numbers or two non-function values".to_owned(),
+                    })
+                }
+
                 // Allow implicit string conversions
                 (lhs, rhs) => {
                     let mut lhs = lhs.stringify();
                     lhs.push_str(rhs.stringify().as_str());
-                    Value::String(lhs)
+                    Value::String(lhs.into())
+                }
+            },
+            // Truncated remainder, so the result takes the sign of the dividend: -7 % 3 == -1
+            OperatorType::Percent => match (left, right) {
+                (Value::Number(lhs), Value::Number(rhs)) => Value::Number(lhs % rhs),
+                _ => {
+                    return Err(RuntimeError::InvalidOperands {
+                        operator,
+                        expected: "two numbers".to_owned(),
+                    })
                 }
             },
             OperatorType::Slash => match (left, right) {
@@ -238,8 +729,8 @@ impl Interpreter {
                 }
             },
 
-            OperatorType::BangEqual => Value::Boolean(left != right),
-            OperatorType::EqualEqual => Value::Boolean(left == right),
+            OperatorType::BangEqual => Value::Boolean(!self.values_equal(&left, &right)),
+            OperatorType::EqualEqual => Value::Boolean(self.values_equal(&left, &right)),
             OperatorType::Greater => match (left, right) {
                 (Value::Number(lhs), Value::Number(rhs)) => Value::Boolean(lhs > rhs),
                 _ => {
@@ -277,16 +768,17 @@ impl Interpreter {
                 }
             },
 
-            // Invalid operators in this situation
-            OperatorType::Equal => {
-                panic!("Should never get '=' as an operator between two values in this state")
-            }
-            OperatorType::Bang => {
-                panic!("Should never get '!' as an operator between two values in this state")
+            // The parser never produces these between two values, but a hand-built AST might
+            OperatorType::Equal
+            | OperatorType::Bang
+            | OperatorType::And
+            | OperatorType::Or
+            | OperatorType::QuestionQuestion => {
+                return Err(RuntimeError::InvalidBinaryOperator(operator))
             }
 
-            // Todos
-            OperatorType::Dot => todo!("Used when implementing classes, fields, and methods"),
+            // Not yet supported, will be used when implementing classes, fields, and methods
+            OperatorType::Dot => return Err(RuntimeError::UnsupportedOperator(operator)),
         };
 
         Ok(value)
@@ -0,0 +1,193 @@
+use crate::parser::types::{NativeFunction, Value};
+
+/// Returns all native functions available to Lox programs.
+pub fn all() -> Vec<NativeFunction> {
+    vec![
+        NativeFunction {
+            name: "concat",
+            arity: None,
+            function: concat,
+        },
+        NativeFunction {
+            name: "pow",
+            arity: Some(2),
+            function: pow,
+        },
+        NativeFunction {
+            name: "upper",
+            arity: Some(1),
+            function: upper,
+        },
+        NativeFunction {
+            name: "lower",
+            arity: Some(1),
+            function: lower,
+        },
+        NativeFunction {
+            name: "trim",
+            arity: Some(1),
+            function: trim,
+        },
+        NativeFunction {
+            name: "substr",
+            arity: Some(3),
+            function: substr,
+        },
+        NativeFunction {
+            name: "ord",
+            arity: Some(1),
+            function: ord,
+        },
+        NativeFunction {
+            name: "chr",
+            arity: Some(1),
+            function: chr,
+        },
+        NativeFunction {
+            name: "bool",
+            arity: Some(1),
+            function: bool,
+        },
+        NativeFunction {
+            name: "num",
+            arity: Some(1),
+            function: num,
+        },
+    ]
+}
+
+/// Stringifies all arguments and joins them into a single string.
+fn concat(arguments: &[Value]) -> Result<Value, String> {
+    let string = arguments.iter().map(Value::stringify).collect::<String>();
+
+    Ok(Value::String(string.into()))
+}
+
+/// Raises the first argument to the power of the second argument.
+fn pow(arguments: &[Value]) -> Result<Value, String> {
+    let (Some(base), Some(exp)) = (
+        arguments.first().and_then(Value::as_number),
+        arguments.get(1).and_then(Value::as_number),
+    ) else {
+        return Err("expected two numbers".to_owned());
+    };
+
+    Ok(Value::Number(base.powf(exp)))
+}
+
+/// Converts all characters of a string to uppercase.
+fn upper(arguments: &[Value]) -> Result<Value, String> {
+    let string = string_argument(arguments)?;
+
+    Ok(Value::String(string.to_uppercase().into()))
+}
+
+/// Converts all characters of a string to lowercase.
+fn lower(arguments: &[Value]) -> Result<Value, String> {
+    let string = string_argument(arguments)?;
+
+    Ok(Value::String(string.to_lowercase().into()))
+}
+
+/// Removes leading and trailing whitespace from a string.
+fn trim(arguments: &[Value]) -> Result<Value, String> {
+    let string = string_argument(arguments)?;
+
+    Ok(Value::String(string.trim().into()))
+}
+
+/// Takes `len` characters of a string starting at the character index `start`. Indices count
+/// characters rather than bytes, so multi-byte characters are never split.
+fn substr(arguments: &[Value]) -> Result<Value, String> {
+    let (Some(string), Some(start), Some(len)) = (
+        arguments.first().and_then(Value::as_string),
+        arguments.get(1).and_then(Value::as_number),
+        arguments.get(2).and_then(Value::as_number),
+    ) else {
+        return Err("expected a string and two numbers".to_owned());
+    };
+
+    let (Some(start), Some(len)) = (as_index(start), as_index(len)) else {
+        return Err("expected start and length to be non-negative integers".to_owned());
+    };
+
+    match start.checked_add(len) {
+        Some(end) if end <= string.chars().count() => {}
+        _ => return Err("substring is out of range".to_owned()),
+    }
+
+    Ok(Value::String(
+        string
+            .chars()
+            .skip(start)
+            .take(len)
+            .collect::<String>()
+            .into(),
+    ))
+}
+
+/// Returns the Unicode scalar value of a one-character string.
+fn ord(arguments: &[Value]) -> Result<Value, String> {
+    let string = string_argument(arguments)?;
+
+    let mut chars = string.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(Value::Number(u32::from(c) as f64)),
+        _ => Err("expected a string with exactly one character".to_owned()),
+    }
+}
+
+/// Returns the one-character string for a Unicode code point.
+fn chr(arguments: &[Value]) -> Result<Value, String> {
+    let Some(code_point) = arguments.first().and_then(Value::as_number) else {
+        return Err("expected a number".to_owned());
+    };
+
+    as_index(code_point)
+        .and_then(|code_point| u32::try_from(code_point).ok())
+        .and_then(char::from_u32)
+        .map(|c| Value::String(c.to_string().into()))
+        .ok_or_else(|| format!("{code_point} is not a valid code point"))
+}
+
+/// Converts any value to a boolean according to its truthiness.
+fn bool(arguments: &[Value]) -> Result<Value, String> {
+    let [value] = arguments else {
+        return Err("expected one argument".to_owned());
+    };
+
+    Ok(Value::Boolean(value.to_bool()))
+}
+
+/// Parses a string into a number. Surrounding whitespace, a leading `+` or `-`, and an exponent
+/// like `1e3` are accepted, but infinity and NaN are not, whether spelled out or the result of
+/// overflow like `1e400`. Numbers are returned as is.
+fn num(arguments: &[Value]) -> Result<Value, String> {
+    let string = match arguments {
+        [Value::Number(number)] => return Ok(Value::Number(*number)),
+        [Value::String(string)] => string.trim(),
+        _ => return Err("expected a string or a number".to_owned()),
+    };
+
+    // Rust also parses "inf" and "NaN", which are not numbers in Lox
+    let is_numeric = |c: char| c.is_ascii_digit() || matches!(c, '+' | '-' | '.' | 'e' | 'E');
+    let number = match string.parse::<f64>() {
+        Ok(number) if number.is_finite() && string.chars().all(is_numeric) => number,
+        _ => return Err(format!("cannot convert \"{string}\" to a number")),
+    };
+
+    Ok(Value::Number(number))
+}
+
+/// Returns the first argument if it is a string.
+fn string_argument(arguments: &[Value]) -> Result<&str, String> {
+    arguments
+        .first()
+        .and_then(Value::as_string)
+        .ok_or_else(|| "expected a string".to_owned())
+}
+
+/// Converts `number` into an index if it is a non-negative integer.
+fn as_index(number: f64) -> Option<usize> {
+    (number >= 0.0 && number.fract() == 0.0).then_some(number as usize)
+}
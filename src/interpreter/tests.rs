@@ -0,0 +1,1333 @@
+use std::{cell::RefCell, io::Write, rc::Rc};
+
+use pretty_assertions::assert_eq;
+
+use crate::{
+    lexer::{
+        error::Result,
+        token::{Token, TokenType},
+        Lexer,
+    },
+    parser::{
+        types::{Expr, ExprRef, NumberFormat, Operator, OperatorType, Stmt, Value},
+        Parser,
+    },
+};
+
+use super::{
+    environment::Environment,
+    error::{InvalidFloatEpsilon, RuntimeError},
+    ErrorReporter, Interpreter,
+};
+
+/// Lex, parse, and interpret the lox source code provided by `src` with `interpreter`.
+///
+/// # Panic
+/// Panics if the source code provided has syntax errors.
+fn run(src: &str, interpreter: &mut Interpreter) {
+    let tokens = Lexer::new(src)
+        .scan_all_tokens()
+        .into_iter()
+        .collect::<Result<Vec<Token>>>()
+        .expect("source code should be valid");
+    let statements = Parser::new(tokens)
+        .parse()
+        .expect("source code should be valid");
+
+    interpreter.interpret(statements);
+}
+
+/// A writer that can be handed to an [Interpreter] as its output while still being readable from
+/// the test.
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl SharedBuffer {
+    fn contents(&self) -> String {
+        String::from_utf8(self.0.borrow().clone()).expect("output should be valid UTF-8")
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Create an [Interpreter] whose output is captured by the returned [SharedBuffer].
+fn capturing_interpreter() -> (Interpreter, SharedBuffer) {
+    let output = SharedBuffer::default();
+    let mut interpreter = Interpreter::new();
+    interpreter.set_output(output.clone());
+
+    (interpreter, output)
+}
+
+#[test]
+fn can_read_host_defined_global() {
+    let mut interpreter = Interpreter::new();
+    interpreter.define_global("answer", Value::Number(21.0));
+
+    run("var result = answer * 2;", &mut interpreter);
+
+    let result = interpreter.environment.get(&"result".to_owned()).unwrap();
+    assert_eq!(result, &Value::Number(42.0));
+}
+
+#[test]
+fn define_global_overwrites_existing_variable() {
+    let mut interpreter = Interpreter::new();
+    run("var answer = 1;", &mut interpreter);
+
+    interpreter.define_global("answer", Value::Number(42.0));
+
+    let answer = interpreter.environment.get(&"answer".to_owned()).unwrap();
+    assert_eq!(answer, &Value::Number(42.0));
+}
+
+#[test]
+fn concat_stringifies_and_joins_arguments() {
+    let mut interpreter = Interpreter::new();
+
+    run(r#"var result = concat(1, "x", true);"#, &mut interpreter);
+
+    let result = interpreter.environment.get(&"result".to_owned()).unwrap();
+    assert_eq!(result, &Value::String("1xtrue".into()));
+}
+
+#[test]
+fn string_concatenation_creates_a_new_string() {
+    let mut interpreter = Interpreter::new();
+
+    // Concatenating an empty string yields equal contents, which must still not alias the operand
+    run(
+        r#"var greeting = "Hello"; var copy = greeting + ""; var alias = greeting;"#,
+        &mut interpreter,
+    );
+
+    let (Some(Value::String(greeting)), Some(Value::String(copy)), Some(Value::String(alias))) = (
+        interpreter.get_global("greeting"),
+        interpreter.get_global("copy"),
+        interpreter.get_global("alias"),
+    ) else {
+        panic!("expected all variables to be strings");
+    };
+    assert_eq!(copy, greeting);
+    assert!(!Rc::ptr_eq(greeting, copy));
+    assert!(Rc::ptr_eq(greeting, alias));
+}
+
+#[test]
+fn user_declarations_shadow_natives() {
+    let mut interpreter = Interpreter::new();
+
+    run("var concat = 1; var result = concat;", &mut interpreter);
+
+    let result = interpreter.environment.get(&"result".to_owned()).unwrap();
+    assert_eq!(result, &Value::Number(1.0));
+}
+
+#[test]
+fn errors_in_for_loops_report_source_positions() {
+    let cases = [
+        (
+            "for (var i = 0;\n i < 1;\n i = i + 1) {\n  -\"body\";\n}",
+            4,
+        ),
+        (
+            "for (var i = 0;\n i < 1;\n i = i + -\"increment\") {\n  i;\n}",
+            3,
+        ),
+        ("for (var i = 0;\n -\"condition\";\n i = i + 1) {}", 2),
+    ];
+
+    for (source, expected_line) in cases {
+        let mut interpreter = Interpreter::new();
+        let stmt = crate::parse(source).unwrap().remove(0);
+
+        let error = interpreter.execute(&stmt).unwrap_err();
+
+        assert!(
+            matches!(error, RuntimeError::InvalidUnaryOperatorForValue { .. }),
+            "{source}: {error:?}"
+        );
+        assert_eq!(error.position().map(|(line, _)| line), Some(expected_line));
+    }
+}
+
+#[test]
+fn interpret_collect_returns_every_runtime_error() {
+    let (mut interpreter, output) = capturing_interpreter();
+    let statements = crate::parse("print a;\nprint 1;\nprint -\"b\";\nprint 2;").unwrap();
+
+    let errors = interpreter.interpret_collect(statements);
+    let positions = errors
+        .iter()
+        .map(RuntimeError::position)
+        .collect::<Vec<_>>();
+
+    assert!(matches!(
+        errors.as_slice(),
+        [
+            RuntimeError::UndefinedVariable { .. },
+            RuntimeError::InvalidUnaryOperatorForValue { .. }
+        ]
+    ));
+    assert_eq!(positions, [Some((1, 7)), Some((3, 7))]);
+    assert_eq!(output.contents(), "1\n2\n");
+}
+
+#[test]
+fn trace_hooks_observe_statements_and_expressions() {
+    let (mut interpreter, _) = capturing_interpreter();
+    let statements = Rc::new(RefCell::new(Vec::new()));
+    let expressions = Rc::new(RefCell::new(Vec::new()));
+
+    let recorded = Rc::clone(&statements);
+    interpreter.set_trace_hook(Some(Box::new(move |stmt: &Stmt| {
+        recorded.borrow_mut().push(stmt.name())
+    })));
+    let recorded = Rc::clone(&expressions);
+    interpreter.set_expr_trace_hook(Some(Box::new(move |expr: ExprRef<'_>| {
+        recorded.borrow_mut().push(expr.to_string())
+    })));
+
+    run("var a = 1; if (a) { print a; }", &mut interpreter);
+
+    assert_eq!(
+        *statements.borrow(),
+        [
+            "variable declaration",
+            "if statement",
+            "block",
+            "print statement"
+        ]
+    );
+    assert_eq!(
+        *expressions.borrow(),
+        ["1", "(var Identifier a)", "(var Identifier a)"]
+    );
+
+    interpreter.set_trace_hook(None);
+    run("print a;", &mut interpreter);
+
+    assert_eq!(statements.borrow().len(), 4);
+}
+
+#[test]
+fn repl_expression_statements_reach_trace_hook_and_step_limit() {
+    let (mut interpreter, _) = capturing_interpreter();
+    let statements = Rc::new(RefCell::new(Vec::new()));
+
+    let recorded = Rc::clone(&statements);
+    interpreter.set_trace_hook(Some(Box::new(move |stmt: &Stmt| {
+        recorded.borrow_mut().push(stmt.name())
+    })));
+    interpreter.interpret_repl(crate::parse("1 + 1;").unwrap());
+
+    assert_eq!(*statements.borrow(), ["expression statement"]);
+
+    let errors = Rc::new(RefCell::new(Vec::new()));
+    let reporter: Box<dyn ErrorReporter> = Box::new(CollectingReporter(Rc::clone(&errors)));
+    let mut interpreter = Interpreter::with_reporters([reporter]);
+    interpreter.set_max_steps(Some(0));
+    interpreter.interpret_repl(crate::parse("1 + 1;").unwrap());
+
+    assert_eq!(
+        *errors.borrow(),
+        [RuntimeError::StepLimitExceeded { limit: 0 }]
+    );
+}
+
+#[test]
+fn error_on_calling_non_function() {
+    let mut interpreter = Interpreter::new();
+    let stmt = crate::parse("1();").unwrap().remove(0);
+
+    let result = interpreter.execute(&stmt);
+    let expected = Err(RuntimeError::CallNonCallable {
+        type_name: "number",
+        line: 1,
+        col: 3,
+    });
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn error_on_calling_variable_holding_non_function() {
+    let mut interpreter = Interpreter::new();
+    let mut stmts = crate::parse("var x = 5; x();").unwrap();
+    let call = stmts.pop().unwrap();
+
+    interpreter.execute(&stmts[0]).unwrap();
+    let error = interpreter.execute(&call).unwrap_err();
+
+    assert_eq!(
+        error,
+        RuntimeError::CallNonCallable {
+            type_name: "number",
+            line: 1,
+            col: 14,
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "can only call functions, got a value of type number"
+    );
+}
+
+/// A writer that fails every write, like stdout after the reading end of its pipe is closed.
+struct FailingWriter;
+
+impl Write for FailingWriter {
+    fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::BrokenPipe,
+            "closed",
+        ))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Collects reported errors so that tests can inspect them.
+struct CollectingReporter(Rc<RefCell<Vec<RuntimeError>>>);
+
+impl ErrorReporter for CollectingReporter {
+    fn report_err(&self, error: &RuntimeError) {
+        self.0.borrow_mut().push(error.clone());
+    }
+}
+
+#[test]
+fn error_on_failing_output() {
+    let errors = Rc::new(RefCell::new(Vec::new()));
+    let reporter = CollectingReporter(Rc::clone(&errors));
+    let mut interpreter =
+        Interpreter::with_reporters([Box::new(reporter) as Box<dyn ErrorReporter>]);
+    interpreter.set_output(FailingWriter);
+
+    interpreter.interpret(crate::parse("print 1;").unwrap());
+    interpreter.interpret_repl(crate::parse("1 + 1;").unwrap());
+
+    let expected = RuntimeError::Output {
+        kind: std::io::ErrorKind::BrokenPipe,
+        message: "closed".to_owned(),
+    };
+    assert_eq!(*errors.borrow(), [expected.clone(), expected]);
+}
+
+#[test]
+fn repl_prints_only_print_statement_output() {
+    let (mut interpreter, output) = capturing_interpreter();
+
+    interpreter.interpret_repl(crate::parse("print 1;").unwrap());
+
+    assert_eq!(output.contents(), "1\n");
+}
+
+#[test]
+fn repl_prints_value_of_bare_expression() {
+    let (mut interpreter, output) = capturing_interpreter();
+
+    interpreter.interpret_repl(crate::parse("1 + 1;").unwrap());
+
+    assert_eq!(output.contents(), "2\n");
+}
+
+#[test]
+fn repl_does_not_print_assignments_or_nil() {
+    let (mut interpreter, output) = capturing_interpreter();
+
+    interpreter.interpret_repl(crate::parse("var a; a = 1; nil; a;").unwrap());
+
+    assert_eq!(output.contents(), "1\n");
+}
+
+#[test]
+fn repl_prints_value_returned_by_call() {
+    let (mut interpreter, output) = capturing_interpreter();
+
+    interpreter.interpret_repl(crate::parse(r#"upper("lox");"#).unwrap());
+
+    assert_eq!(output.contents(), "LOX\n");
+}
+
+#[test]
+fn repl_if_without_else_does_nothing_when_condition_is_false() {
+    let (mut interpreter, output) = capturing_interpreter();
+    let stmt = crate::parse("if (false) print 1;").unwrap().remove(0);
+
+    assert_eq!(interpreter.execute_for_value(&stmt), Ok(None));
+    interpreter.interpret_repl(vec![stmt]);
+
+    assert_eq!(output.contents(), "");
+}
+
+#[test]
+fn pow_raises_base_to_exponent() {
+    let mut interpreter = Interpreter::new();
+
+    run("var result = pow(2, 10);", &mut interpreter);
+
+    let result = interpreter.environment.get(&"result".to_owned()).unwrap();
+    assert_eq!(result, &Value::Number(1024.0));
+}
+
+#[test]
+fn error_on_pow_with_non_number() {
+    let mut interpreter = Interpreter::new();
+    let stmt = crate::parse(r#"pow(2, "x");"#).unwrap().remove(0);
+
+    let result = interpreter.execute(&stmt);
+    let expected = Err(RuntimeError::NativeFunctionError {
+        name: "pow".to_owned(),
+        message: "expected two numbers".to_owned(),
+        line: 1,
+        col: 11,
+    });
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn block_scope_is_exited_on_runtime_error() {
+    let mut interpreter = Interpreter::new();
+
+    run("{ var a = 1; print undefined; }", &mut interpreter);
+    assert_eq!(interpreter.environment.depth(), 0);
+
+    // Would fail with `VariableAlreadyDefined` if the block's scope was still active
+    run("var a = 2;", &mut interpreter);
+
+    let a = interpreter.environment.get(&"a".to_owned()).unwrap();
+    assert_eq!(a, &Value::Number(2.0));
+}
+
+#[test]
+fn execute_block_sees_prepared_scope_and_enclosing_variables() {
+    let (mut interpreter, output) = capturing_interpreter();
+    run("var greeting = \"hi\";", &mut interpreter);
+
+    let mut scope = Environment::new();
+    scope
+        .define("name".to_owned(), Value::String("lox".into()))
+        .unwrap();
+    let stmts = crate::parse("print greeting; print name; var inner = 1;").unwrap();
+
+    interpreter.execute_block(&stmts, scope).unwrap();
+
+    assert_eq!(output.contents(), "hi\nlox\n");
+    assert_eq!(interpreter.environment.depth(), 0);
+    assert_eq!(interpreter.get_global("name"), None);
+    assert_eq!(interpreter.get_global("inner"), None);
+}
+
+#[test]
+fn execute_block_restores_scope_on_error() {
+    let mut interpreter = Interpreter::new();
+    let stmts = crate::parse("print undefined;").unwrap();
+
+    let result = interpreter.execute_block(&stmts, Environment::new());
+
+    assert!(result.is_err());
+    assert_eq!(interpreter.environment.depth(), 0);
+}
+
+#[test]
+fn nil_coalesce_returns_right_operand_when_left_is_nil() {
+    let mut interpreter = Interpreter::new();
+
+    run("var a; var result = a ?? 2;", &mut interpreter);
+
+    let result = interpreter.environment.get(&"result".to_owned()).unwrap();
+    assert_eq!(result, &Value::Number(2.0));
+}
+
+#[test]
+fn nil_coalesce_skips_right_operand_when_left_is_not_nil() {
+    let mut interpreter = Interpreter::new();
+
+    run(
+        "var evaluated = false; var result = 0 ?? (evaluated = true);",
+        &mut interpreter,
+    );
+
+    let result = interpreter.environment.get(&"result".to_owned()).unwrap();
+    assert_eq!(result, &Value::Number(0.0));
+    let evaluated = interpreter
+        .environment
+        .get(&"evaluated".to_owned())
+        .unwrap();
+    assert_eq!(evaluated, &Value::Boolean(false));
+}
+
+#[test]
+fn break_exits_innermost_loop() {
+    let mut interpreter = Interpreter::new();
+
+    run(
+        "var i = 0; while (true) { i = i + 1; if (i == 3) break; }",
+        &mut interpreter,
+    );
+
+    let i = interpreter.environment.get(&"i".to_owned()).unwrap();
+    assert_eq!(i, &Value::Number(3.0));
+}
+
+#[test]
+fn labeled_break_exits_outer_loop_from_inner_loop() {
+    let mut interpreter = Interpreter::new();
+
+    run(
+        r#"
+        var iterations = 0;
+        outer: for (var i = 0; i < 3; i = i + 1) {
+            for (var j = 0; j < 3; j = j + 1) {
+                iterations = iterations + 1;
+                if (j == 1) break outer;
+            }
+        }
+        "#,
+        &mut interpreter,
+    );
+
+    let iterations = interpreter
+        .environment
+        .get(&"iterations".to_owned())
+        .unwrap();
+    assert_eq!(iterations, &Value::Number(2.0));
+    assert_eq!(interpreter.environment.depth(), 0);
+}
+
+#[test]
+fn print_displays_values_like_lox() {
+    let cases = [
+        ("print 1;", "1\n"),
+        ("print 1.5;", "1.5\n"),
+        ("print -3;", "-3\n"),
+        ("print 0 - 2.5;", "-2.5\n"),
+        ("print 100000000000000000000;", "100000000000000000000\n"),
+        (r#"print "hi";"#, "hi\n"),
+        ("print true;", "true\n"),
+        ("print nil;", "nil\n"),
+    ];
+
+    for (source, expected) in cases {
+        let (mut interpreter, output) = capturing_interpreter();
+
+        run(source, &mut interpreter);
+
+        assert_eq!(output.contents(), expected, "source: {source}");
+    }
+}
+
+#[test]
+fn unary_plus_is_identity_for_numbers() {
+    let mut interpreter = Interpreter::new();
+
+    run("var a = +5; var b = -+5;", &mut interpreter);
+
+    assert_eq!(interpreter.get_global("a"), Some(&Value::Number(5.0)));
+    assert_eq!(interpreter.get_global("b"), Some(&Value::Number(-5.0)));
+}
+
+#[test]
+fn error_on_unary_plus_with_non_number() {
+    let mut interpreter = Interpreter::new();
+    let stmt = crate::parse(r#"+"x";"#).unwrap().remove(0);
+
+    let result = interpreter.execute(&stmt);
+    let expected = RuntimeError::InvalidUnaryOperatorForValue {
+        operator: Operator {
+            operator_type: OperatorType::Plus,
+            src_line: 1,
+            src_col: 1,
+        },
+        value: Value::String("x".into()),
+    };
+
+    assert_eq!(result, Err(expected));
+}
+
+#[test]
+fn print_native_function() {
+    let (mut interpreter, output) = capturing_interpreter();
+
+    run("print pow;", &mut interpreter);
+
+    assert_eq!(output.contents(), "<native fn pow>\n");
+}
+
+#[test]
+fn error_on_implicit_string_conversion_of_function() {
+    let mut interpreter = Interpreter::new();
+    let stmt = crate::parse(r#""x" + concat;"#).unwrap().remove(0);
+
+    let result = interpreter.execute(&stmt);
+    let expected = Err(RuntimeError::InvalidOperands {
+        operator: Operator {
+            operator_type: OperatorType::Plus,
+            src_line: 1,
+            src_col: 5,
+        },
+        expected: "two numbers or two non-function values".to_owned(),
+    });
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn error_on_dot_operator_in_binary_expression() {
+    let mut interpreter = Interpreter::new();
+    let operator = Operator {
+        operator_type: OperatorType::Dot,
+        src_line: 1,
+        src_col: 2,
+    };
+    let expr = Expr::binary(
+        Expr::literal(Value::Number(1.0)),
+        operator.clone(),
+        Expr::literal(Value::Number(2.0)),
+    );
+
+    let result = interpreter.evaluate(expr.root());
+
+    assert_eq!(result, Err(RuntimeError::UnsupportedOperator(operator)));
+}
+
+#[test]
+fn modulo_takes_sign_of_dividend() {
+    let mut interpreter = Interpreter::new();
+
+    run(
+        "var a = 7 % 3; var b = -7 % 3; var c = 7 % -3; var d = -7 % -3; var e = 5.5 % 2;",
+        &mut interpreter,
+    );
+
+    let expected = [("a", 1.0), ("b", -1.0), ("c", 1.0), ("d", -1.0), ("e", 1.5)];
+    for (name, value) in expected {
+        let result = interpreter.environment.get(&name.to_owned()).unwrap();
+        assert_eq!(result, &Value::Number(value), "variable: {name}");
+    }
+}
+
+#[test]
+fn can_read_global_after_running_script() {
+    let mut interpreter = Interpreter::new();
+
+    run("var answer = 42;", &mut interpreter);
+
+    assert_eq!(interpreter.get_global("answer"), Some(&Value::Number(42.0)));
+    assert_eq!(interpreter.get_global("question"), None);
+}
+
+#[test]
+fn can_read_const() {
+    let mut interpreter = Interpreter::new();
+
+    run("const a = 1; var b = a + 1;", &mut interpreter);
+
+    assert_eq!(interpreter.get_global("b"), Some(&Value::Number(2.0)));
+}
+
+#[test]
+fn error_on_assign_to_const() {
+    let mut interpreter = Interpreter::new();
+    run("const a = 1;", &mut interpreter);
+    let stmt = crate::parse("a = 2;").unwrap().remove(0);
+
+    let result = interpreter.execute(&stmt);
+    let expected = Err(RuntimeError::AssignToConst {
+        name: "a".to_owned(),
+        line: 1,
+        col: 1,
+    });
+
+    assert_eq!(result, expected);
+    assert_eq!(interpreter.get_global("a"), Some(&Value::Number(1.0)));
+}
+
+#[test]
+fn const_can_be_shadowed_in_nested_scope() {
+    let mut interpreter = Interpreter::new();
+
+    run(
+        "const a = 1; var b; { var a = 2; a = 3; b = a; }",
+        &mut interpreter,
+    );
+
+    assert_eq!(interpreter.get_global("a"), Some(&Value::Number(1.0)));
+    assert_eq!(interpreter.get_global("b"), Some(&Value::Number(3.0)));
+}
+
+#[test]
+fn string_case_and_trim_natives() {
+    let mut interpreter = Interpreter::new();
+
+    run(
+        r#"var a = upper("Hello"); var b = lower("Hello"); var c = trim("  hi there  ");"#,
+        &mut interpreter,
+    );
+
+    let expected = [("a", "HELLO"), ("b", "hello"), ("c", "hi there")];
+    for (name, value) in expected {
+        let result = interpreter.get_global(name);
+        assert_eq!(
+            result,
+            Some(&Value::String(value.into())),
+            "variable: {name}"
+        );
+    }
+}
+
+#[test]
+fn bool_native_converts_by_truthiness() {
+    let mut interpreter = Interpreter::new();
+
+    run(
+        r#"var a = bool(0); var b = bool(nil); var c = bool("x"); var d = bool(2); var e = bool(false);"#,
+        &mut interpreter,
+    );
+
+    let expected = [
+        ("a", false),
+        ("b", false),
+        ("c", true),
+        ("d", true),
+        ("e", false),
+    ];
+    for (name, value) in expected {
+        let result = interpreter.get_global(name);
+        assert_eq!(result, Some(&Value::Boolean(value)), "variable: {name}");
+    }
+}
+
+#[test]
+fn num_native_parses_common_numeric_strings() {
+    let mut interpreter = Interpreter::new();
+
+    run(
+        r#"var a = num("+1.5"); var b = num(" 3 "); var c = num("1e3"); var d = num("-2"); var e = num(4);"#,
+        &mut interpreter,
+    );
+
+    let expected = [
+        ("a", 1.5),
+        ("b", 3.0),
+        ("c", 1000.0),
+        ("d", -2.0),
+        ("e", 4.0),
+    ];
+    for (name, value) in expected {
+        let result = interpreter.get_global(name);
+        assert_eq!(result, Some(&Value::Number(value)), "{name}");
+    }
+}
+
+#[test]
+fn error_on_num_native_with_invalid_string() {
+    for source in [
+        r#"num("1.5x");"#,
+        r#"num("");"#,
+        r#"num("inf");"#,
+        r#"num("1 2");"#,
+        r#"num("1e400");"#,
+    ] {
+        let mut interpreter = Interpreter::new();
+        let stmt = crate::parse(source).unwrap().remove(0);
+
+        let result = interpreter.execute(&stmt);
+
+        assert!(
+            matches!(
+                result,
+                Err(RuntimeError::NativeFunctionError { ref message, .. })
+                    if message.starts_with("cannot convert")
+            ),
+            "{source}: {result:?}"
+        );
+    }
+}
+
+#[test]
+fn error_on_string_native_with_non_string() {
+    let mut interpreter = Interpreter::new();
+    let stmt = crate::parse("upper(1);").unwrap().remove(0);
+
+    let result = interpreter.execute(&stmt);
+    let expected = Err(RuntimeError::NativeFunctionError {
+        name: "upper".to_owned(),
+        message: "expected a string".to_owned(),
+        line: 1,
+        col: 8,
+    });
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn substr_takes_characters_from_start() {
+    let mut interpreter = Interpreter::new();
+
+    run(
+        r#"var a = substr("hello", 1, 3); var b = substr("héllo", 0, 2);"#,
+        &mut interpreter,
+    );
+
+    assert_eq!(
+        interpreter.get_global("a"),
+        Some(&Value::String("ell".into()))
+    );
+    assert_eq!(
+        interpreter.get_global("b"),
+        Some(&Value::String("hé".into()))
+    );
+}
+
+#[test]
+fn error_on_substr_out_of_range() {
+    let mut interpreter = Interpreter::new();
+    let stmt = crate::parse(r#"substr("hello", 3, 3);"#).unwrap().remove(0);
+
+    let result = interpreter.execute(&stmt);
+    let expected = Err(RuntimeError::NativeFunctionError {
+        name: "substr".to_owned(),
+        message: "substring is out of range".to_owned(),
+        line: 1,
+        col: 21,
+    });
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn error_on_substr_with_huge_index() {
+    let sources = [
+        r#"substr("abc", 18446744073709551615, 1);"#,
+        r#"substr("abc", 1, 18446744073709551615);"#,
+    ];
+
+    for source in sources {
+        let mut interpreter = Interpreter::new();
+        let stmt = crate::parse(source).unwrap().remove(0);
+
+        let result = interpreter.execute(&stmt);
+
+        assert!(
+            matches!(
+                result,
+                Err(RuntimeError::NativeFunctionError { ref message, .. })
+                    if message == "substring is out of range"
+            ),
+            "{source}: {result:?}"
+        );
+    }
+}
+
+#[test]
+fn index_returns_character_of_string() {
+    let mut interpreter = Interpreter::new();
+
+    run(
+        r#"var a = "hello"[1]; var b = "héllo"[1]; var s = "abc"; var c = s[1 + 1];"#,
+        &mut interpreter,
+    );
+
+    assert_eq!(
+        interpreter.get_global("a"),
+        Some(&Value::String("e".into()))
+    );
+    assert_eq!(
+        interpreter.get_global("b"),
+        Some(&Value::String("é".into()))
+    );
+    assert_eq!(
+        interpreter.get_global("c"),
+        Some(&Value::String("c".into()))
+    );
+}
+
+#[test]
+fn error_on_index_out_of_range() {
+    let mut interpreter = Interpreter::new();
+    let stmt = crate::parse(r#""hello"[5];"#).unwrap().remove(0);
+
+    let result = interpreter.execute(&stmt);
+    let expected = Err(RuntimeError::IndexOutOfRange {
+        index: 5.0,
+        len: 5,
+        line: 1,
+        col: 10,
+    });
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn error_on_invalid_index() {
+    let sources = [
+        (r#""hello"[-1];"#, Value::Number(-1.0)),
+        (r#""hello"[0.5];"#, Value::Number(0.5)),
+        (r#""hello"["0"];"#, Value::String("0".into())),
+    ];
+
+    for (source, index) in sources {
+        let mut interpreter = Interpreter::new();
+        let stmt = crate::parse(source).unwrap().remove(0);
+
+        let result = interpreter.execute(&stmt);
+
+        assert!(
+            matches!(result, Err(RuntimeError::InvalidIndex { index: ref got, .. }) if *got == index),
+            "{source}: {result:?}"
+        );
+    }
+}
+
+#[test]
+fn error_on_indexing_non_string() {
+    let mut interpreter = Interpreter::new();
+    let stmt = crate::parse("123[0];").unwrap().remove(0);
+
+    let result = interpreter.execute(&stmt);
+    let expected = Err(RuntimeError::IndexNonString {
+        type_name: "number",
+        line: 1,
+        col: 6,
+    });
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn ord_and_chr_convert_between_characters_and_code_points() {
+    let mut interpreter = Interpreter::new();
+
+    run(
+        r#"var a = ord("A"); var b = chr(65); var c = chr(ord("é"));"#,
+        &mut interpreter,
+    );
+
+    assert_eq!(interpreter.get_global("a"), Some(&Value::Number(65.0)));
+    assert_eq!(
+        interpreter.get_global("b"),
+        Some(&Value::String("A".into()))
+    );
+    assert_eq!(
+        interpreter.get_global("c"),
+        Some(&Value::String("é".into()))
+    );
+}
+
+#[test]
+fn error_on_invalid_ord_and_chr_arguments() {
+    let mut interpreter = Interpreter::new();
+    let ord_stmt = crate::parse(r#"ord("ab");"#).unwrap().remove(0);
+    let chr_stmt = crate::parse("chr(55296);").unwrap().remove(0);
+
+    let ord_result = interpreter.execute(&ord_stmt);
+    let chr_result = interpreter.execute(&chr_stmt);
+
+    let ord_expected = Err(RuntimeError::NativeFunctionError {
+        name: "ord".to_owned(),
+        message: "expected a string with exactly one character".to_owned(),
+        line: 1,
+        col: 9,
+    });
+    let chr_expected = Err(RuntimeError::NativeFunctionError {
+        name: "chr".to_owned(),
+        message: "55296 is not a valid code point".to_owned(),
+        line: 1,
+        col: 10,
+    });
+
+    assert_eq!(ord_result, ord_expected);
+    assert_eq!(chr_result, chr_expected);
+}
+
+#[test]
+fn print_uses_number_format() {
+    let (mut interpreter, output) = capturing_interpreter();
+
+    run("print 3.14159;", &mut interpreter);
+    interpreter.set_number_format(NumberFormat::Fixed(2));
+    run("print 3.14159; print 2;", &mut interpreter);
+    interpreter.set_number_format(NumberFormat::Scientific);
+    run("print 1500;", &mut interpreter);
+
+    assert_eq!(output.contents(), "3.14159\n3.14\n2.00\n1.5e3\n");
+}
+
+#[test]
+fn numbers_compare_exactly_by_default() {
+    let mut interpreter = Interpreter::new();
+
+    run(
+        "var equal = 0.1 + 0.2 == 0.3; var not_equal = 0.1 + 0.2 != 0.3;",
+        &mut interpreter,
+    );
+
+    assert_eq!(
+        interpreter.get_global("equal"),
+        Some(&Value::Boolean(false))
+    );
+    assert_eq!(
+        interpreter.get_global("not_equal"),
+        Some(&Value::Boolean(true))
+    );
+}
+
+#[test]
+fn numbers_compare_within_float_epsilon() {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_float_epsilon(Some(1e-9)).unwrap();
+
+    run(
+        "var equal = 0.1 + 0.2 == 0.3; var not_equal = 0.1 + 0.2 != 0.3; var far = 1 == 1.1;",
+        &mut interpreter,
+    );
+
+    assert_eq!(interpreter.get_global("equal"), Some(&Value::Boolean(true)));
+    assert_eq!(
+        interpreter.get_global("not_equal"),
+        Some(&Value::Boolean(false))
+    );
+    assert_eq!(interpreter.get_global("far"), Some(&Value::Boolean(false)));
+}
+
+#[test]
+fn negative_float_epsilon_is_rejected() {
+    let mut interpreter = Interpreter::new();
+
+    let result = interpreter.set_float_epsilon(Some(-1.0));
+
+    assert_eq!(result, Err(InvalidFloatEpsilon(-1.0)));
+}
+
+#[test]
+fn nan_float_epsilon_is_rejected() {
+    let mut interpreter = Interpreter::new();
+
+    let result = interpreter.set_float_epsilon(Some(f64::NAN));
+
+    // NaN is not equal to itself, so the error cannot be compared directly
+    assert!(
+        matches!(result, Err(InvalidFloatEpsilon(epsilon)) if epsilon.is_nan()),
+        "{result:?}"
+    );
+}
+
+#[test]
+fn error_on_nil_arithmetic_operand() {
+    let mut interpreter = Interpreter::new();
+    run("var a;", &mut interpreter);
+    let stmt = crate::parse("a - 1;").unwrap().remove(0);
+
+    let result = interpreter.execute(&stmt);
+    let expected = RuntimeError::NilOperand {
+        operator: Operator {
+            operator_type: OperatorType::Minus,
+            src_line: 1,
+            src_col: 3,
+        },
+    };
+
+    assert_eq!(
+        expected.to_string(),
+        "operand of '-' is nil, is a variable uninitialized?"
+    );
+    assert_eq!(result, Err(expected));
+}
+
+#[test]
+fn error_on_exceeding_loop_limit() {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_max_loop_iterations(Some(10));
+    let stmt = crate::parse("while (true) {}").unwrap().remove(0);
+
+    let result = interpreter.execute(&stmt);
+
+    assert_eq!(result, Err(RuntimeError::LoopLimitExceeded { limit: 10 }));
+}
+
+#[test]
+fn loops_within_limit_run_to_completion() {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_max_loop_iterations(Some(10));
+
+    run(
+        "var i = 0; while (i < 5) i = i + 1; while (i < 10) i = i + 1;",
+        &mut interpreter,
+    );
+
+    assert_eq!(interpreter.get_global("i"), Some(&Value::Number(10.0)));
+}
+
+#[test]
+fn error_on_exceeding_step_limit() {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_max_steps(Some(100));
+    let stmt = crate::parse("while (true) { 1 + 2; }").unwrap().remove(0);
+
+    let result = interpreter.execute(&stmt);
+
+    assert_eq!(result, Err(RuntimeError::StepLimitExceeded { limit: 100 }));
+}
+
+#[test]
+fn statements_within_step_limit_run_to_completion() {
+    let mut interpreter = Interpreter::new();
+    // One step for the statement, one for the binary expression, and one for each literal
+    interpreter.set_max_steps(Some(4));
+
+    run("var a = 1 + 2;", &mut interpreter);
+
+    assert_eq!(interpreter.get_global("a"), Some(&Value::Number(3.0)));
+}
+
+#[test]
+fn and_or_return_deciding_operand() {
+    let mut interpreter = Interpreter::new();
+
+    run(
+        r#"var a = nil or "x"; var b = 1 and 2; var c = nil and 2; var d = "y" or 2;"#,
+        &mut interpreter,
+    );
+
+    assert_eq!(
+        interpreter.get_global("a"),
+        Some(&Value::String("x".into()))
+    );
+    assert_eq!(interpreter.get_global("b"), Some(&Value::Number(2.0)));
+    assert_eq!(interpreter.get_global("c"), Some(&Value::Nil));
+    assert_eq!(
+        interpreter.get_global("d"),
+        Some(&Value::String("y".into()))
+    );
+}
+
+#[test]
+fn and_or_short_circuit_left_to_right() {
+    let mut interpreter = Interpreter::new();
+
+    // Assignments record which operands were evaluated
+    run(
+        "var a = 0; var b = 0; var c = 0; var d = 0;
+        false and (a = 1);
+        true or (b = 1);
+        (c = 1) and false and (d = 1);",
+        &mut interpreter,
+    );
+
+    let expected = [("a", 0.0), ("b", 0.0), ("c", 1.0), ("d", 0.0)];
+    for (name, value) in expected {
+        let result = interpreter.get_global(name);
+        assert_eq!(result, Some(&Value::Number(value)), "variable: {name}");
+    }
+}
+
+#[test]
+fn labeled_break_out_of_for_loop_is_not_an_error() {
+    let mut interpreter = Interpreter::new();
+
+    let result = crate::run(
+        "outer: for (var i = 0; i < 3; i = i + 1) { break outer; }",
+        &mut interpreter,
+    );
+
+    assert_eq!(result, Ok(()));
+}
+
+#[test]
+fn break_outside_of_loop_stops_only_its_statement() {
+    let (mut interpreter, output) = capturing_interpreter();
+    let keyword = Token {
+        token_type: TokenType::Break,
+        line: 1,
+        col: 5,
+    };
+    let statements = vec![
+        Stmt::Block(vec![
+            Stmt::Break {
+                keyword,
+                label: None,
+            },
+            Stmt::Print(literal(1.0)),
+        ]),
+        Stmt::Print(literal(2.0)),
+    ];
+
+    let errors = interpreter.interpret_collect(statements);
+
+    assert_eq!(errors, []);
+    assert_eq!(output.contents(), "2\n");
+}
+
+#[test]
+fn labeled_block_introduces_scope() {
+    let mut interpreter = Interpreter::new();
+
+    run(
+        "var a = 1; setup: { var a = 2; var b = 3; }",
+        &mut interpreter,
+    );
+
+    assert_eq!(interpreter.get_global("a"), Some(&Value::Number(1.0)));
+    assert_eq!(interpreter.get_global("b"), None);
+    assert_eq!(interpreter.environment.depth(), 0);
+}
+
+#[test]
+fn natives_are_callable_with_arity() {
+    let interpreter = Interpreter::new();
+
+    let pow = interpreter.natives["pow"].clone();
+    let concat = interpreter.natives["concat"].clone();
+    let number = Value::Number(1.0);
+
+    assert!(pow.is_callable());
+    assert_eq!(pow.arity(), Some(2));
+    assert!(concat.is_callable());
+    assert_eq!(concat.arity(), None);
+    assert!(!number.is_callable());
+    assert_eq!(number.arity(), None);
+}
+
+#[test]
+fn values_convert_from_rust_values() {
+    let mut interpreter = Interpreter::new();
+
+    interpreter.define_global("number", 3.0);
+    interpreter.define_global("boolean", true);
+    interpreter.define_global("owned", "owned".to_owned());
+    interpreter.define_global("borrowed", "borrowed");
+    interpreter.define_global("nothing", ());
+
+    assert_eq!(interpreter.get_global("number"), Some(&Value::Number(3.0)));
+    assert_eq!(
+        interpreter.get_global("boolean"),
+        Some(&Value::Boolean(true))
+    );
+    assert_eq!(
+        interpreter.get_global("owned"),
+        Some(&Value::String("owned".into()))
+    );
+    assert_eq!(
+        interpreter.get_global("borrowed"),
+        Some(&Value::String("borrowed".into()))
+    );
+    assert_eq!(interpreter.get_global("nothing"), Some(&Value::Nil));
+}
+
+#[test]
+fn values_convert_into_rust_values() {
+    assert_eq!(f64::try_from(Value::Number(3.0)), Ok(3.0));
+    assert_eq!(bool::try_from(Value::Boolean(true)), Ok(true));
+    assert_eq!(
+        String::try_from(Value::String("lox".into())),
+        Ok("lox".to_owned())
+    );
+
+    assert_eq!(Value::Number(3.0).as_number(), Some(3.0));
+    assert_eq!(Value::Boolean(false).as_bool(), Some(false));
+    assert_eq!(Value::String("lox".into()).as_string(), Some("lox"));
+    assert_eq!(Value::Nil.as_number(), None);
+}
+
+#[test]
+fn error_on_converting_value_of_wrong_type() {
+    let result = f64::try_from(Value::String("3".into()));
+    let expected = RuntimeError::TypeMismatch {
+        expected: "a number".to_owned(),
+        got: Value::String("3".into()),
+    };
+
+    assert_eq!(expected.to_string(), "expected a number, got \"3\"");
+    assert_eq!(result, Err(expected));
+    assert!(bool::try_from(Value::Nil).is_err());
+    assert!(String::try_from(Value::Number(1.0)).is_err());
+}
+
+fn number_token() -> Token {
+    Token {
+        token_type: TokenType::Number(1.0),
+        line: 1,
+        col: 1,
+    }
+}
+
+fn operator(operator_type: OperatorType) -> Operator {
+    Operator {
+        operator_type,
+        src_line: 1,
+        src_col: 3,
+    }
+}
+
+fn literal(value: f64) -> Expr {
+    Expr::literal(Value::Number(value))
+}
+
+#[test]
+fn error_on_hand_built_declaration_without_identifier() {
+    let mut interpreter = Interpreter::new();
+    let statements = [
+        Stmt::Var {
+            name: number_token(),
+            initializer: None,
+        },
+        Stmt::Const {
+            name: number_token(),
+            initializer: literal(1.0),
+        },
+        Stmt::Expression(Expr::variable(number_token())),
+    ];
+
+    for stmt in statements {
+        let result = interpreter.execute(&stmt);
+        let expected = RuntimeError::InvalidVariableName(number_token());
+
+        assert_eq!(result, Err(expected.clone()), "statement: {stmt}");
+        assert_eq!(expected.to_string(), "Number is not a valid variable name");
+    }
+}
+
+#[test]
+fn error_on_hand_built_binary_expression_with_invalid_operator() {
+    let mut interpreter = Interpreter::new();
+
+    for operator_type in [
+        OperatorType::Equal,
+        OperatorType::Bang,
+        OperatorType::And,
+        OperatorType::Or,
+        OperatorType::QuestionQuestion,
+    ] {
+        let expr = Expr::binary(literal(1.0), operator(operator_type.clone()), literal(2.0));
+
+        let result = interpreter.evaluate(expr.root());
+        let expected = RuntimeError::InvalidBinaryOperator(operator(operator_type));
+
+        assert_eq!(result, Err(expected), "expression: {expr}");
+    }
+}
+
+#[test]
+fn error_on_hand_built_logical_expression_with_invalid_operator() {
+    let mut interpreter = Interpreter::new();
+    let expr = Expr::logical(literal(1.0), operator(OperatorType::Plus), literal(2.0));
+
+    let result = interpreter.evaluate(expr.root());
+    let expected = RuntimeError::InvalidLogicalOperator(operator(OperatorType::Plus));
+
+    assert_eq!(expected.to_string(), "invalid logical operator '+'");
+    assert_eq!(result, Err(expected));
+}
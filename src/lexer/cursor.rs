@@ -19,46 +19,46 @@ pub trait Peekable {
     }
 }
 
-/// A cursor over a [Vec<char>](std::alloc::Vec)
+/// A cursor over a [String] that tracks its position as a byte index into the source
 #[derive(Debug)]
 pub struct Cursor {
-    stack: Vec<char>,
+    source: String,
     needle: usize,
 }
 
 impl Cursor {
-    pub fn new(source: &str) -> Self {
+    /// Creates a cursor over `source`, taking ownership of it without copying when given a
+    /// [String].
+    pub fn new(source: impl Into<String>) -> Self {
         Self {
-            stack: source.chars().collect(),
+            source: source.into(),
             needle: 0,
         }
     }
 
-    /// Gets a substring that starts and ends at the specified indicies, exclusive.
-    /// Returns `None` if the one or both of the indices given are invalid.
-    pub fn substring(&self, start: usize, end: usize) -> Option<String> {
-        let stack_len = self.stack.len();
-
-        let substring_option = if start > stack_len || end > stack_len || start > end {
-            return None;
-        } else {
-            let slice = &self.stack[start..end];
-            let slice = slice.iter().collect();
-
-            Some(slice)
-        };
-
-        substring_option
+    /// Gets a substring that starts and ends at the specified byte indicies, exclusive.
+    /// Returns `None` if the one or both of the indices given are invalid or do not fall on a
+    /// `char` boundary.
+    pub fn substring(&self, start: usize, end: usize) -> Option<&str> {
+        self.source.get(start..end)
     }
 
     // For testing only
     #[cfg(test)]
+    #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize {
-        self.stack.len()
+        self.source.len()
+    }
+
+    /// Moves the cursor back to the byte index `needle`, which must not be past the current
+    /// position.
+    pub fn rewind_to(&mut self, needle: usize) {
+        debug_assert!(needle <= self.needle, "cannot rewind the cursor forwards");
+        self.needle = needle;
     }
 
     pub fn is_at_end(&self) -> bool {
-        self.needle >= self.stack.len()
+        self.needle >= self.source.len()
     }
 }
 
@@ -66,11 +66,11 @@ impl Iterator for Cursor {
     type Item = char;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let c = self.stack.get(self.needle)?;
+        let c = self.source[self.needle..].chars().next()?;
 
-        self.needle += 1;
+        self.needle += c.len_utf8();
 
-        Some(*c)
+        Some(c)
     }
 }
 
@@ -78,14 +78,14 @@ impl Peekable for Cursor {
     type Item = char;
 
     fn peek_nth(&self, n: usize) -> Option<Self::Item> {
-        match self.needle.checked_add(n) {
-            Some(target) if target < self.stack.len() => Some(self.stack[target]),
-            _ => None,
-        }
+        self.source[self.needle..].chars().nth(n)
     }
 
     fn peek_prev_nth(&self, n: usize) -> Option<Self::Item> {
-        self.needle.checked_sub(n).map(|target| self.stack[target])
+        match n.checked_sub(1) {
+            Some(n) => self.source[..self.needle].chars().rev().nth(n),
+            None => self.peek(),
+        }
     }
 }
 
@@ -124,4 +124,20 @@ mod tests {
 
         assert_eq!(cursor.substring(start, end), None);
     }
+
+    #[test]
+    fn substring_borrows_multi_byte_slices() {
+        let cursor = Cursor::new("héllo, wörld");
+
+        // 'é' and 'ö' are both two bytes wide
+        assert_eq!(cursor.substring(0, 6), Some("héllo"));
+        assert_eq!(cursor.substring(8, 14), Some("wörld"));
+    }
+
+    #[test]
+    fn substring_return_none_when_not_on_char_boundary() {
+        let cursor = Cursor::new("héllo");
+
+        assert_eq!(cursor.substring(0, 2), None);
+    }
 }
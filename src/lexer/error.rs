@@ -19,6 +19,20 @@ pub enum LexerError {
         col: usize,
         message: String,
     },
+    InvalidEscapeSequence {
+        sequence: String,
+        line: usize,
+        col: usize,
+    },
+    /// An integer literal too large to be represented exactly by a number. Only an error in
+    /// strict mode, a warning otherwise.
+    InexactInteger {
+        lexeme: String,
+        /// The number the literal is rounded to
+        value: f64,
+        line: usize,
+        col: usize,
+    },
 }
 
 impl LexerError {
@@ -27,30 +41,51 @@ impl LexerError {
             LexerError::UnterminatedString { .. } => "UnterminatedString",
             LexerError::UnexpectedCharacter { .. } => "UnexpectedCharacter",
             LexerError::FloatParsingError { .. } => "FloatParsingError",
+            LexerError::InvalidEscapeSequence { .. } => "InvalidEscapeSequence",
+            LexerError::InexactInteger { .. } => "InexactInteger",
         }
     }
 }
 
-impl Display for LexerError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let (line, col, error_name, msg) = match self {
-            LexerError::UnterminatedString { line, col } => {
-                (line, col, self.name(), "".to_string())
-            }
-            LexerError::UnexpectedCharacter {
-                character,
-                line,
-                col,
-            } => (line, col, self.name(), character.to_string()),
+impl LexerError {
+    /// Returns the line and column in the source code where the error occurred.
+    pub fn position(&self) -> Option<(usize, usize)> {
+        match self {
+            LexerError::UnterminatedString { line, col }
+            | LexerError::UnexpectedCharacter { line, col, .. }
+            | LexerError::FloatParsingError { line, col, .. }
+            | LexerError::InvalidEscapeSequence { line, col, .. }
+            | LexerError::InexactInteger { line, col, .. } => Some((*line, *col)),
+        }
+    }
+
+    /// Returns the error message without the position prefix.
+    pub fn message(&self) -> String {
+        let msg = match self {
+            LexerError::UnterminatedString { .. } => "".to_string(),
+            LexerError::UnexpectedCharacter { character, .. } => character.to_string(),
             LexerError::FloatParsingError {
-                lexeme,
-                line,
-                col,
-                message,
-            } => (line, col, self.name(), format!("{}, {}", message, lexeme)),
+                lexeme, message, ..
+            } => format!("invalid number literal '{lexeme}' ({message})"),
+            LexerError::InvalidEscapeSequence { sequence, .. } => sequence.to_owned(),
+            LexerError::InexactInteger { lexeme, value, .. } => {
+                format!(
+                    "integer literal '{lexeme}' cannot be represented exactly and becomes {value}"
+                )
+            }
         };
 
-        write!(f, "[line {}: col {}] {}: {}", line, col, error_name, msg)
+        format!("{}: {}", self.name(), msg)
+    }
+}
+
+impl Display for LexerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (line, col) = self
+            .position()
+            .expect("lexer errors should always have a position");
+
+        write!(f, "[line {}: col {}] {}", line, col, self.message())
     }
 }
 
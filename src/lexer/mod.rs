@@ -16,7 +16,9 @@ lazy_static! {
     static ref KEYWORDS: HashMap<&'static str, TokenType> = {
         let mut map = HashMap::new();
         map.insert("and", TokenType::And);
+        map.insert("break", TokenType::Break);
         map.insert("class", TokenType::Class);
+        map.insert("const", TokenType::Const);
         map.insert("else", TokenType::Else);
         map.insert("false", TokenType::False);
         map.insert("for", TokenType::For);
@@ -42,32 +44,137 @@ pub struct Lexer {
     current: usize,
     line: usize,
     col: usize,
+    /// Whether to keep lexing the rest of the source after an unterminated string
+    recover: bool,
+    /// Reserved words of the language, or `None` to use the default ones
+    keywords: Option<HashMap<String, TokenType>>,
+    /// Whether to emit comments as tokens instead of skipping them
+    trivia: bool,
+    /// Whether problems that are otherwise warnings are errors
+    strict: bool,
+    /// Problems found so far that do not stop lexing
+    warnings: Vec<LexerError>,
 }
 
 impl Lexer {
-    pub fn new(source: &str) -> Self {
+    /// Creates a lexer over `source`, which can be borrowed (`&str`) or owned (`String`).
+    pub fn new(source: impl Into<String>) -> Self {
         Self {
             source: Cursor::new(source),
             start: 0,
             current: 0,
             line: 1,
             col: 0,
+            recover: false,
+            keywords: None,
+            trivia: false,
+            strict: false,
+            warnings: Vec::new(),
         }
     }
 
+    /// Creates a lexer over `source` that emits a [TokenType::Comment] token for every comment
+    /// instead of skipping it, for tools like formatters that need to keep comments. The parser
+    /// does not accept comment tokens, so they must be filtered out before parsing.
+    pub fn with_trivia(source: impl Into<String>) -> Self {
+        Self {
+            trivia: true,
+            ..Self::new(source)
+        }
+    }
+
+    /// Creates a lexer over `source` that uses `keywords` as its reserved words instead of the
+    /// default ones. Start from [Lexer::default_keywords] to add or rename only some keywords.
+    pub fn new_with_keywords(
+        source: impl Into<String>,
+        keywords: HashMap<String, TokenType>,
+    ) -> Self {
+        Self {
+            keywords: Some(keywords),
+            ..Self::new(source)
+        }
+    }
+
+    /// Returns the reserved words used by [Lexer::new], mapped to the token they produce.
+    pub fn default_keywords() -> HashMap<String, TokenType> {
+        KEYWORDS
+            .iter()
+            .map(|(keyword, token_type)| (keyword.to_string(), token_type.clone()))
+            .collect()
+    }
+
+    /// Enables or disables recovery from unterminated strings. When enabled, an unterminated
+    /// string is still reported but only extends to, and is reported at, the end of its first
+    /// line. Lexing resumes from there instead of stopping at the end of the source. Useful for
+    /// editors that want tokens for the rest of a file that is being edited.
+    pub fn set_recovery(&mut self, recover: bool) {
+        self.recover = recover;
+    }
+
+    /// Enables or disables strict mode. When enabled, problems that are otherwise only warnings,
+    /// like integer literals that lose precision, are returned as errors instead.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Returns the warnings found since the last call, see [Lexer::set_strict]. Iterate the lexer
+    /// with [Iterator::by_ref] to still have access to it afterwards.
+    pub fn take_warnings(&mut self) -> Vec<LexerError> {
+        std::mem::take(&mut self.warnings)
+    }
+
     pub fn scan_token(&mut self) -> Option<Result<Token>> {
-        self.start = self.current;
+        loop {
+            self.skip_whitespace();
+            self.start = self.current;
+
+            let c = self.advance()?;
+            let token_type = match self.scan_token_type(c) {
+                Ok(token_type) => token_type,
+                Err(e) => return Some(Err(e)),
+            };
+
+            // Comments are passed over like whitespace unless trivia is kept
+            match token_type {
+                TokenType::Comment(_) if !self.trivia => continue,
+                _ => return Some(Ok(self.create_token(token_type))),
+            }
+        }
+    }
 
-        let c = self.advance()?;
-        let token_type_result = match c {
+    /// Consumes a run of whitespace, keeping track of lines and columns, so that it does not
+    /// need to be scanned one token at a time.
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.source.peek() {
+            match c {
+                ' ' | '\r' | '\t' => {
+                    self.advance();
+                }
+                '\n' => {
+                    self.advance();
+                    self.line += 1;
+                    self.col = 0;
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// Scans the rest of the token starting with `c`, which has already been consumed.
+    fn scan_token_type(&mut self, c: char) -> Result<TokenType> {
+        match c {
             // Single letter tokens
             '(' => Ok(TokenType::LeftParen),
             ')' => Ok(TokenType::RightParen),
             '{' => Ok(TokenType::LeftBrace),
             '}' => Ok(TokenType::RightBrace),
+            '[' => Ok(TokenType::LeftBracket),
+            ']' => Ok(TokenType::RightBracket),
+            ':' => Ok(TokenType::Colon),
             ',' => Ok(TokenType::Comma),
             '.' => Ok(TokenType::Dot),
             '-' => Ok(TokenType::Minus),
+            '%' => Ok(TokenType::Percent),
             '+' => Ok(TokenType::Plus),
             ';' => Ok(TokenType::Semicolon),
             '*' => Ok(TokenType::Star),
@@ -105,6 +212,10 @@ impl Lexer {
                     Ok(TokenType::Greater)
                 }
             }
+            '?' if self.source.peek() == Some('?') => {
+                self.advance();
+                Ok(TokenType::QuestionQuestion)
+            }
 
             // Multi-letter tokens
             '/' => {
@@ -112,7 +223,7 @@ impl Lexer {
                     while self.source.peek() != Some('\n') && !self.is_at_end() {
                         self.advance();
                     }
-                    Ok(TokenType::Comment)
+                    Ok(TokenType::Comment(self.get_lexeme().to_owned()))
                 } else {
                     Ok(TokenType::Slash)
                 }
@@ -123,17 +234,6 @@ impl Lexer {
             'A'..='Z' => self.handle_indentifier(),
             '_' => self.handle_indentifier(),
 
-            // Whitespace
-            ' ' | '\r' | '\t' => Ok(TokenType::Whitespace),
-            '\n' => {
-                // Increment line counter
-                self.line += 1;
-                // Reset col counter
-                self.col = 0;
-
-                Ok(TokenType::Whitespace)
-            }
-
             unexpected => {
                 self.start = self.current;
                 Err(LexerError::UnexpectedCharacter {
@@ -142,17 +242,6 @@ impl Lexer {
                     col: self.col,
                 })
             }
-        };
-
-        let token_type = match token_type_result {
-            Ok(token_type) => token_type,
-            Err(e) => return Some(Err(e)),
-        };
-
-        // If the token is whitespace or a comment, simply pass over to the next token
-        match token_type {
-            TokenType::Whitespace | TokenType::Comment => self.scan_token(),
-            _ => Some(Ok(self.create_token(token_type))),
         }
     }
 
@@ -169,12 +258,15 @@ impl Lexer {
     /// Advance needle of [Cursor] and corresponding bookkeeping of [Lexer]
     #[inline]
     fn advance(&mut self) -> Option<char> {
-        let next_char = self.source.next();
+        let next_char = self.source.next()?;
 
-        self.col += 1;
-        self.current += 1;
+        // A `\r\n` line ending counts as a single character, the same as a lone `\n`
+        if next_char != '\r' || self.source.peek() != Some('\n') {
+            self.col += 1;
+        }
+        self.current += next_char.len_utf8();
 
-        next_char
+        Some(next_char)
     }
 
     #[inline]
@@ -187,7 +279,7 @@ impl Lexer {
     }
 
     #[inline]
-    fn get_lexeme(&self) -> String {
+    fn get_lexeme(&self) -> &str {
         self.source
             .substring(self.start, self.current)
             .expect("positions `Lexer.start` and `Lexer.end` should always be valid")
@@ -195,14 +287,34 @@ impl Lexer {
 
     #[inline]
     fn handle_string_literal(&mut self) -> Result<TokenType> {
+        let quote = (self.line, self.col);
+        // Where to resume lexing if the string turns out to be unterminated in recovery mode
+        let mut first_newline = None;
+
         while self.source.peek() != Some('"') && !self.is_at_end() {
             if self.source.peek() == Some('\n') {
+                first_newline.get_or_insert((self.current, self.line, self.col));
                 self.line += 1;
             }
+
+            // Skip over escaped quotes and backslashes so that `\"` does not end the string
+            if self.source.peek() == Some('\\')
+                && matches!(self.source.peek_nth(1), Some('"' | '\\'))
+            {
+                self.advance();
+            }
             self.advance();
         }
 
         if self.is_at_end() {
+            // When recovering, the string ends at the end of its first line, so report it there
+            if let Some((current, line, col)) = first_newline.filter(|_| self.recover) {
+                self.source.rewind_to(current);
+                self.current = current;
+                self.line = line;
+                self.col = col;
+            }
+
             Err(LexerError::UnterminatedString {
                 line: self.line,
                 col: self.col,
@@ -210,13 +322,91 @@ impl Lexer {
         } else {
             // The closing "
             self.advance();
-            let lexeme = self.get_lexeme().chars().collect::<Vec<char>>();
-            // trim surrounding quotes
-            let literal = lexeme[1..lexeme.len() - 1].iter().collect::<String>();
+            let lexeme = self.get_lexeme();
+            // trim surrounding quotes, which are always a single byte each
+            let literal = self.unescape_string(&lexeme[1..lexeme.len() - 1], quote)?;
+            // Multi-line strings contain `\n` line endings regardless of how the file was saved
+            let literal = if literal.contains("\r\n") {
+                literal.replace("\r\n", "\n")
+            } else {
+                literal
+            };
             Ok(TokenType::String(literal))
         }
     }
 
+    /// Decodes the escape sequences supported in string literals. Backslashes that do not start
+    /// a supported escape sequence are kept as is.
+    ///
+    /// Supported escape sequences:
+    /// - `\"`: a double quote
+    /// - `\\`: a backslash
+    /// - `\u{...}`: a Unicode code point of one to six hex digits
+    ///
+    /// `quote` is the position of the literal's opening quote, used to report where an invalid
+    /// escape sequence starts.
+    #[inline]
+    fn unescape_string(&self, raw: &str, quote: (usize, usize)) -> Result<String> {
+        let mut literal = String::with_capacity(raw.len());
+        let mut chars = raw.char_indices().peekable();
+
+        while let Some((offset, c)) = chars.next() {
+            if c == '\\' {
+                if let Some((_, escaped)) = chars.next_if(|(_, next)| matches!(next, '"' | '\\')) {
+                    literal.push(escaped);
+                    continue;
+                }
+            }
+
+            if c != '\\' || chars.next_if(|(_, next)| *next == 'u').is_none() {
+                literal.push(c);
+                continue;
+            }
+
+            let mut sequence = "\\u".to_owned();
+            let mut closed = false;
+            if let Some((_, open_brace)) = chars.next_if(|(_, next)| *next == '{') {
+                sequence.push(open_brace);
+
+                for (_, c) in chars.by_ref() {
+                    sequence.push(c);
+                    if c == '}' {
+                        closed = true;
+                        break;
+                    }
+                }
+            }
+
+            // Strip the leading `\u{` and trailing `}`
+            let hex_digits = &sequence[3.min(sequence.len())..sequence.len() - closed as usize];
+            // `from_str_radix` would also accept a leading sign
+            let code_point = if closed
+                && (1..=6).contains(&hex_digits.len())
+                && hex_digits.chars().all(|c| c.is_ascii_hexdigit())
+            {
+                u32::from_str_radix(hex_digits, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+            } else {
+                None
+            };
+
+            match code_point {
+                Some(c) => literal.push(c),
+                None => {
+                    let (line, col) = position_in_string(raw, offset, quote);
+                    return Err(LexerError::InvalidEscapeSequence {
+                        sequence,
+                        line,
+                        col,
+                    });
+                }
+            }
+        }
+
+        Ok(literal)
+    }
+
     #[inline]
     fn handle_numeric_literal(&mut self) -> Result<TokenType> {
         while let Some(next_char) = self.source.peek() {
@@ -244,15 +434,33 @@ impl Lexer {
         }
 
         let lexeme = self.get_lexeme();
+        // Point at the start of the number rather than its end
+        let col = self.col + 1 - lexeme.chars().count();
         let number = lexeme
             .parse::<f64>()
             .map_err(|e| LexerError::FloatParsingError {
-                lexeme,
+                lexeme: lexeme.to_owned(),
                 line: self.line,
-                col: self.col,
+                col,
                 message: e.to_string(),
             })?;
 
+        // Formatting without decimals prints the exact integer the literal was rounded to
+        let digits = lexeme.trim_start_matches('0');
+        if !lexeme.contains('.') && !digits.is_empty() && format!("{number:.0}") != digits {
+            let inexact = LexerError::InexactInteger {
+                lexeme: lexeme.to_owned(),
+                value: number,
+                line: self.line,
+                col,
+            };
+
+            if self.strict {
+                return Err(inexact);
+            }
+            self.warnings.push(inexact);
+        }
+
         Ok(TokenType::Number(number))
     }
 
@@ -269,14 +477,34 @@ impl Lexer {
 
         let literal = self.get_lexeme();
 
-        if let Some(keyword_type) = KEYWORDS.get(literal.as_str()) {
+        let keyword = match self.keywords {
+            Some(ref keywords) => keywords.get(literal),
+            None => KEYWORDS.get(literal),
+        };
+
+        if let Some(keyword_type) = keyword {
             Ok(keyword_type.clone())
         } else {
-            Ok(TokenType::Identifier(literal))
+            Ok(TokenType::Identifier(literal.to_owned()))
         }
     }
 }
 
+/// Returns the position of the character at byte `offset` in the contents of a string literal
+/// whose opening quote is at `quote`.
+fn position_in_string(contents: &str, offset: usize, quote: (usize, usize)) -> (usize, usize) {
+    let (line, col) = quote;
+    let before = &contents[..offset];
+
+    match before.rfind('\n') {
+        Some(newline) => (
+            line + before.matches('\n').count(),
+            before[newline + 1..].chars().count() + 1,
+        ),
+        None => (line, col + before.chars().count() + 1),
+    }
+}
+
 impl Iterator for Lexer {
     type Item = self::error::Result<Token>;
 
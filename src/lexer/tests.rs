@@ -122,6 +122,120 @@ fn can_scan_string_literal() {
     assert_eq!(tokens, expected);
 }
 
+#[test]
+fn can_scan_multi_byte_string_literal() {
+    let source = "\"héllo, wörld\"";
+    let lexer = Lexer::new(source);
+
+    let tokens = lexer.scan_all_tokens();
+    let Some(Ok(Token {
+        token_type: TokenType::String(literal),
+        line,
+        col,
+    })) = tokens.into_iter().next()
+    else {
+        panic!("expected a string literal token");
+    };
+
+    assert_eq!(literal, "héllo, wörld");
+    assert_eq!(line, 1);
+    assert_eq!(col, source.chars().count());
+}
+
+#[test]
+fn can_scan_unicode_escape_sequences() {
+    let source = r#""\u{41}\u{1F600}""#;
+    let lexer = Lexer::new(source);
+
+    let tokens = lexer.scan_all_tokens();
+    let Some(Ok(Token {
+        token_type: TokenType::String(literal),
+        ..
+    })) = tokens.into_iter().next()
+    else {
+        panic!("expected a string literal token");
+    };
+
+    assert_eq!(literal, "A\u{1F600}");
+}
+
+#[test]
+fn can_scan_escaped_quotes_and_backslashes() {
+    let sources = [
+        (r#""she said \"hi\"""#, r#"she said "hi""#),
+        (r#""C:\\dir\\""#, r"C:\dir\"),
+        (r#""\\\"""#, r#"\""#),
+        (r#""\n""#, r"\n"),
+    ];
+
+    for (source, expected) in sources {
+        let tokens = Lexer::new(source).scan_all_tokens();
+        let [Ok(Token {
+            token_type: TokenType::String(literal),
+            ..
+        })] = tokens.as_slice()
+        else {
+            panic!("expected a single string literal token for source: {source}");
+        };
+
+        assert_eq!(literal, expected, "source: {source}");
+    }
+}
+
+#[test]
+fn error_on_invalid_unicode_escape_sequences() {
+    let sources = [
+        (r#""\u{XYZ}""#, r"\u{XYZ}"),
+        (r#""\u{110000}""#, r"\u{110000}"),
+        (r#""\u{41""#, r"\u{41"),
+        (r#""\u41""#, r"\u"),
+        (r#""\u{+41}""#, r"\u{+41}"),
+    ];
+
+    for (source, sequence) in sources {
+        let tokens = Lexer::new(source).scan_all_tokens();
+        // At the backslash that starts the sequence
+        let expected = [Err(LexerError::InvalidEscapeSequence {
+            sequence: sequence.to_owned(),
+            line: 1,
+            col: 2,
+        })];
+
+        assert_eq!(tokens, expected, "source: {source}");
+    }
+}
+
+#[test]
+fn error_on_invalid_escape_sequence_reports_its_own_line() {
+    let source = "var a = \"first\n  \\u{XYZ}\";";
+
+    let tokens = Lexer::new(source).scan_all_tokens();
+    let expected = Err(LexerError::InvalidEscapeSequence {
+        sequence: r"\u{XYZ}".to_owned(),
+        line: 2,
+        col: 3,
+    });
+
+    assert_eq!(tokens[3], expected);
+}
+
+#[test]
+fn can_scan_brackets() {
+    let token_types: Vec<_> = Lexer::new("s[0]")
+        .map(|token| token.unwrap().token_type)
+        .collect();
+
+    assert!(matches!(
+        token_types.as_slice(),
+        [
+            TokenType::Identifier(_),
+            TokenType::LeftBracket,
+            TokenType::Number(_),
+            TokenType::RightBracket,
+        ]
+    ));
+}
+
 #[test]
 fn can_scan_booleans() {
     let source = "true false";
@@ -176,13 +290,13 @@ fn can_scan_rust_use_statement() {
             line: 1,
             col: 10,
         }),
-        Err(LexerError::UnexpectedCharacter {
-            character: ':',
+        Ok(Token {
+            token_type: TokenType::Colon,
             line: 1,
             col: 11,
         }),
-        Err(LexerError::UnexpectedCharacter {
-            character: ':',
+        Ok(Token {
+            token_type: TokenType::Colon,
             line: 1,
             col: 12,
         }),
@@ -223,6 +337,120 @@ fn can_scan_rust_use_statement() {
     }
 }
 
+#[test]
+fn can_recover_from_non_terminated_string() {
+    let source = "print \"oops;\nvar a;";
+    let mut lexer = Lexer::new(source);
+    lexer.set_recovery(true);
+
+    let tokens = lexer.scan_all_tokens();
+    let expected = [
+        Ok(Token {
+            token_type: TokenType::Print,
+            line: 1,
+            col: 5,
+        }),
+        Err(LexerError::UnterminatedString { line: 1, col: 12 }),
+        Ok(Token {
+            token_type: TokenType::Var,
+            line: 2,
+            col: 3,
+        }),
+        Ok(Token {
+            token_type: TokenType::Identifier("a".to_owned()),
+            line: 2,
+            col: 5,
+        }),
+        Ok(Token {
+            token_type: TokenType::Semicolon,
+            line: 2,
+            col: 6,
+        }),
+    ];
+
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn error_on_malformed_number() {
+    // '²' counts as numeric while scanning but cannot be parsed as part of a float
+    let source = "var a = 12²;";
+
+    let tokens = Lexer::new(source).scan_all_tokens();
+    let error = LexerError::FloatParsingError {
+        lexeme: "12²".to_owned(),
+        line: 1,
+        col: 9,
+        message: "invalid float literal".to_owned(),
+    };
+
+    assert_eq!(
+        error.to_string(),
+        "[line 1: col 9] FloatParsingError: invalid number literal '12²' (invalid float literal)"
+    );
+    assert_eq!(
+        tokens[2],
+        Ok(Token {
+            token_type: TokenType::Equal,
+            line: 1,
+            col: 7
+        })
+    );
+    assert_eq!(tokens[3], Err(error));
+}
+
+#[test]
+fn can_scan_past_long_runs_of_whitespace() {
+    let source = format!("a{}{}  \t b", " ".repeat(100_000), "\n\r\n".repeat(50_000));
+
+    let tokens = Lexer::new(&source).scan_all_tokens();
+    let positions: Vec<_> = tokens
+        .iter()
+        .map(|token| {
+            let token = token.as_ref().unwrap();
+            (token.token_type.name(), token.line, token.col)
+        })
+        .collect();
+
+    assert_eq!(
+        positions,
+        [("Identifier", 1, 1), ("Identifier", 100_001, 5)]
+    );
+}
+
+#[test]
+fn can_scan_windows_line_endings() {
+    let source = "var a;\r\nprint \"x\r\ny\";\r\n";
+
+    let tokens = Lexer::new(source).scan_all_tokens();
+    let positions: Vec<_> = tokens
+        .iter()
+        .map(|token| {
+            let token = token.as_ref().unwrap();
+            (token.token_type.name(), token.line, token.col)
+        })
+        .collect();
+    let expected = [
+        ("Var", 1, 3),
+        ("Identifier", 1, 5),
+        ("Semicolon", 1, 6),
+        ("Print", 2, 5),
+        ("String", 3, 11),
+        ("Semicolon", 3, 12),
+    ];
+
+    assert_eq!(positions, expected);
+
+    let Ok(Token {
+        token_type: TokenType::String(ref literal),
+        ..
+    }) = tokens[4]
+    else {
+        panic!("expected a string literal token");
+    };
+    assert_eq!(literal, "x\ny");
+}
+
 #[test]
 fn error_on_non_terminated_string() {
     let source = "\"Hello, world!";
@@ -236,7 +464,7 @@ fn error_on_non_terminated_string() {
 
 #[test]
 fn error_on_common_unexpected_characters() {
-    let source = "@ # $ % ^ & | \\ : ' ?";
+    let source = "@ # $ ~ ^ & | \\ ' ?";
     let lexer = Lexer::new(source);
 
     let tokens = lexer.scan_all_tokens();
@@ -257,7 +485,7 @@ fn error_on_common_unexpected_characters() {
             col: 5,
         }),
         Err(LexerError::UnexpectedCharacter {
-            character: '%',
+            character: '~',
             line: 1,
             col: 7,
         }),
@@ -281,20 +509,15 @@ fn error_on_common_unexpected_characters() {
             line: 1,
             col: 15,
         }),
-        Err(LexerError::UnexpectedCharacter {
-            character: ':',
-            line: 1,
-            col: 17,
-        }),
         Err(LexerError::UnexpectedCharacter {
             character: '\'',
             line: 1,
-            col: 19,
+            col: 17,
         }),
         Err(LexerError::UnexpectedCharacter {
             character: '?',
             line: 1,
-            col: 21,
+            col: 19,
         }),
     ];
 
@@ -408,3 +631,217 @@ fn can_scan_variable_declaration() {
 
     assert_eq!(tokens, expected);
 }
+
+#[test]
+fn can_scan_question_question() {
+    let source = "a ?? b";
+    let lexer = Lexer::new(source);
+
+    let tokens = lexer.scan_all_tokens();
+    let expected = [
+        Ok(Token {
+            token_type: TokenType::Identifier("a".to_owned()),
+            line: 1,
+            col: 1,
+        }),
+        Ok(Token {
+            token_type: TokenType::QuestionQuestion,
+            line: 1,
+            col: 4,
+        }),
+        Ok(Token {
+            token_type: TokenType::Identifier("b".to_owned()),
+            line: 1,
+            col: 6,
+        }),
+    ];
+
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn can_scan_labeled_break() {
+    let source = "outer: break outer;";
+    let lexer = Lexer::new(source);
+
+    let tokens = lexer.scan_all_tokens();
+    let expected = [
+        Ok(Token {
+            token_type: TokenType::Identifier("outer".to_owned()),
+            line: 1,
+            col: 5,
+        }),
+        Ok(Token {
+            token_type: TokenType::Colon,
+            line: 1,
+            col: 6,
+        }),
+        Ok(Token {
+            token_type: TokenType::Break,
+            line: 1,
+            col: 12,
+        }),
+        Ok(Token {
+            token_type: TokenType::Identifier("outer".to_owned()),
+            line: 1,
+            col: 18,
+        }),
+        Ok(Token {
+            token_type: TokenType::Semicolon,
+            line: 1,
+            col: 19,
+        }),
+    ];
+
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn can_scan_owned_and_borrowed_sources() {
+    let owned = String::from("var x = 1;");
+    let borrowed = owned.as_str();
+
+    let from_borrowed = Lexer::new(borrowed).scan_all_tokens();
+    let from_owned = Lexer::new(owned.clone()).scan_all_tokens();
+
+    assert_eq!(from_borrowed.len(), 5);
+    assert_eq!(from_owned, from_borrowed);
+}
+
+#[test]
+fn can_scan_custom_keywords() {
+    let mut keywords = Lexer::default_keywords();
+    keywords.remove("fun");
+    keywords.insert("function".to_owned(), TokenType::Fun);
+    let lexer = Lexer::new_with_keywords("function fun", keywords);
+
+    let tokens = lexer.scan_all_tokens();
+    let expected = [
+        Ok(Token {
+            token_type: TokenType::Fun,
+            line: 1,
+            col: 8,
+        }),
+        Ok(Token {
+            token_type: TokenType::Identifier("fun".to_owned()),
+            line: 1,
+            col: 12,
+        }),
+    ];
+
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn can_classify_token_types() {
+    let keyword = TokenType::While;
+    let identifier = TokenType::Identifier("x".to_owned());
+    let number = TokenType::Number(1.0);
+    let operator = TokenType::EqualEqual;
+
+    assert!(keyword.is_keyword() && !keyword.is_literal() && !keyword.is_operator());
+    assert!(!identifier.is_keyword() && !identifier.is_literal() && !identifier.is_operator());
+    assert!(!number.is_keyword() && number.is_literal() && !number.is_operator());
+    assert!(!operator.is_keyword() && !operator.is_literal() && operator.is_operator());
+
+    // Logical keywords are also operators
+    for logical in [TokenType::And, TokenType::Or] {
+        assert!(logical.is_keyword() && !logical.is_literal() && logical.is_operator());
+    }
+}
+
+#[test]
+fn can_scan_comments_in_trivia_mode() {
+    let source = "var a; // first\n// second\nprint a;";
+
+    let tokens: Vec<_> = Lexer::with_trivia(source)
+        .scan_all_tokens()
+        .into_iter()
+        .map(Result::unwrap)
+        .collect();
+    let comments: Vec<_> = tokens
+        .iter()
+        .filter_map(|token| match token.token_type {
+            TokenType::Comment(ref text) => Some((text.as_str(), token.line, token.col)),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(tokens.len(), 8);
+    assert!(matches!(tokens[3].token_type, TokenType::Comment(_)));
+    assert_eq!(comments, [("// first", 1, 15), ("// second", 2, 9)]);
+}
+
+#[test]
+fn skips_comments_by_default() {
+    let source = "var a; // first\nprint a;";
+
+    let tokens = Lexer::new(source).scan_all_tokens();
+
+    assert!(tokens
+        .iter()
+        .all(|token| !matches!(token.as_ref().unwrap().token_type, TokenType::Comment(_))));
+    assert_eq!(tokens.len(), 6);
+}
+
+#[test]
+fn warn_on_inexact_integer_literal() {
+    let mut lexer = Lexer::new("1 + 9007199254740993;");
+
+    let tokens: Vec<_> = lexer.by_ref().collect();
+    let expected = [LexerError::InexactInteger {
+        lexeme: "9007199254740993".to_owned(),
+        value: 9007199254740992.0,
+        line: 1,
+        col: 5,
+    }];
+
+    assert_eq!(tokens.len(), 4);
+    assert!(tokens.iter().all(Result::is_ok));
+    assert_eq!(lexer.take_warnings(), expected);
+    assert_eq!(
+        expected[0].to_string(),
+        "[line 1: col 5] InexactInteger: integer literal '9007199254740993' cannot be represented exactly and becomes 9007199254740992"
+    );
+}
+
+#[test]
+fn error_on_inexact_integer_literal_in_strict_mode() {
+    let mut lexer = Lexer::new("9007199254740993");
+    lexer.set_strict(true);
+
+    let tokens = lexer.scan_all_tokens();
+    let expected = [Err(LexerError::InexactInteger {
+        lexeme: "9007199254740993".to_owned(),
+        value: 9007199254740992.0,
+        line: 1,
+        col: 1,
+    })];
+
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn no_warning_on_exact_number_literals() {
+    let mut lexer = Lexer::new("0 007 9007199254740992 0.1 18446744073709551616");
+
+    assert!(lexer.by_ref().all(|token| token.is_ok()));
+    assert_eq!(lexer.take_warnings(), []);
+}
+
+#[test]
+fn tokens_display_with_their_literal() {
+    let token = |token_type| Token {
+        token_type,
+        line: 1,
+        col: 1,
+    };
+
+    assert_eq!(token(TokenType::Eof).to_string(), "<eof>");
+    assert_eq!(token(TokenType::Plus).to_string(), "Plus");
+    assert_eq!(token(TokenType::Number(1.5)).to_string(), "Number 1.5");
+    assert_eq!(
+        token(TokenType::Identifier("a".to_owned())).to_string(),
+        "Identifier a"
+    );
+}
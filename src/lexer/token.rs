@@ -10,9 +10,12 @@ pub struct Token {
 impl Display for Token {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let literal = match &self.token_type {
+            TokenType::Identifier(name) => name.to_string(),
             TokenType::String(str_literal) => str_literal.to_string(),
             TokenType::Number(num_literal) => num_literal.to_string(),
-            _ => "".to_string(),
+            TokenType::Comment(comment) => comment.to_string(),
+            TokenType::Eof => return write!(f, "<eof>"),
+            _ => return write!(f, "{}", self.token_type.name()),
         };
         write!(f, "{} {}", self.token_type.name(), literal)
     }
@@ -22,6 +25,21 @@ impl Token {
     pub fn is_identifier(&self) -> bool {
         matches!(self.token_type, TokenType::Identifier(_))
     }
+
+    /// See [TokenType::is_keyword]
+    pub fn is_keyword(&self) -> bool {
+        self.token_type.is_keyword()
+    }
+
+    /// See [TokenType::is_literal]
+    pub fn is_literal(&self) -> bool {
+        self.token_type.is_literal()
+    }
+
+    /// See [TokenType::is_operator]
+    pub fn is_operator(&self) -> bool {
+        self.token_type.is_operator()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -31,9 +49,13 @@ pub enum TokenType {
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
+    Colon,
     Comma,
     Dot,
     Minus,
+    Percent,
     Plus,
     Semicolon,
     Slash,
@@ -48,6 +70,7 @@ pub enum TokenType {
     GreaterEqual,
     Less,
     LessEqual,
+    QuestionQuestion,
 
     // Literals.
     Identifier(String),
@@ -56,7 +79,9 @@ pub enum TokenType {
 
     // Keywords.
     And,
+    Break,
     Class,
+    Const,
     Else,
     False,
     Fun,
@@ -73,8 +98,8 @@ pub enum TokenType {
     While,
 
     // Ignored
-    Comment,
-    Whitespace,
+    /// A `//` comment including the slashes, only emitted by [Lexer::with_trivia](super::Lexer::with_trivia)
+    Comment(String),
     Eof,
 }
 
@@ -86,9 +111,13 @@ impl TokenType {
             TokenType::RightParen => "RightParen",
             TokenType::LeftBrace => "LeftBrace",
             TokenType::RightBrace => "RightBrace",
+            TokenType::LeftBracket => "LeftBracket",
+            TokenType::RightBracket => "RightBracket",
+            TokenType::Colon => "Colon",
             TokenType::Comma => "Comma",
             TokenType::Dot => "Dot",
             TokenType::Minus => "Minus",
+            TokenType::Percent => "Percent",
             TokenType::Plus => "Plus",
             TokenType::Semicolon => "Semicolon",
             TokenType::Slash => "Slash",
@@ -101,11 +130,14 @@ impl TokenType {
             TokenType::GreaterEqual => "GreaterEqual",
             TokenType::Less => "Less",
             TokenType::LessEqual => "LessEqual",
+            TokenType::QuestionQuestion => "QuestionQuestion",
             TokenType::Identifier(_) => "Identifier",
             TokenType::String(_) => "String",
             TokenType::Number(_) => "Number",
             TokenType::And => "And",
+            TokenType::Break => "Break",
             TokenType::Class => "Class",
+            TokenType::Const => "Const",
             TokenType::Else => "Else",
             TokenType::False => "False",
             TokenType::Fun => "Fun",
@@ -120,11 +152,64 @@ impl TokenType {
             TokenType::True => "True",
             TokenType::Var => "Var",
             TokenType::While => "While",
-            TokenType::Comment => "Comment",
-            TokenType::Whitespace => "Whitespace",
+            TokenType::Comment(_) => "Comment",
             TokenType::Eof => "Eof",
         }
     }
+
+    /// Returns true if the variant is a reserved keyword
+    pub fn is_keyword(&self) -> bool {
+        matches!(
+            self,
+            TokenType::And
+                | TokenType::Break
+                | TokenType::Class
+                | TokenType::Const
+                | TokenType::Else
+                | TokenType::False
+                | TokenType::Fun
+                | TokenType::For
+                | TokenType::If
+                | TokenType::Nil
+                | TokenType::Or
+                | TokenType::Print
+                | TokenType::Return
+                | TokenType::Super
+                | TokenType::This
+                | TokenType::True
+                | TokenType::Var
+                | TokenType::While
+        )
+    }
+
+    /// Returns true if the variant is a string or number literal
+    pub fn is_literal(&self) -> bool {
+        matches!(self, TokenType::String(_) | TokenType::Number(_))
+    }
+
+    /// Returns true if the variant can be converted into an operator
+    pub fn is_operator(&self) -> bool {
+        matches!(
+            self,
+            TokenType::Dot
+                | TokenType::Minus
+                | TokenType::Percent
+                | TokenType::Plus
+                | TokenType::Slash
+                | TokenType::Star
+                | TokenType::Bang
+                | TokenType::BangEqual
+                | TokenType::Equal
+                | TokenType::EqualEqual
+                | TokenType::Greater
+                | TokenType::GreaterEqual
+                | TokenType::Less
+                | TokenType::LessEqual
+                | TokenType::And
+                | TokenType::Or
+                | TokenType::QuestionQuestion
+        )
+    }
 }
 
 impl PartialEq for TokenType {
@@ -1,3 +1,198 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    error::{Diagnostic, RloxError, RloxErrors, Severity},
+    interpreter::Interpreter,
+    lexer::{token::Token, Lexer, LexerError},
+    parser::types::Value,
+    parser::{types::Stmt, Parser},
+    resolver::Resolver,
+};
+
+pub mod error;
 pub mod interpreter;
 pub mod lexer;
 pub mod parser;
+pub mod render;
+pub mod repl;
+pub mod resolver;
+
+/// Scans all tokens in `source`.
+///
+/// # Errors
+///
+/// Returns the first [LexerError] encountered while scanning.
+pub fn lex(source: &str) -> Result<Vec<Token>, LexerError> {
+    Lexer::new(source).scan_all_tokens().into_iter().collect()
+}
+
+/// Scans all tokens in `source` like [lex], but keeps scanning after an error so that every
+/// error in `source` is found.
+///
+/// # Errors
+///
+/// Returns every [LexerError] encountered, in the order they were found.
+pub fn lex_all(source: &str) -> Result<Vec<Token>, Vec<LexerError>> {
+    lex_with_warnings(source).0
+}
+
+/// Scans all tokens in `source` like [lex_all], also returning every warning found.
+fn lex_with_warnings(source: &str) -> (Result<Vec<Token>, Vec<LexerError>>, Vec<LexerError>) {
+    let mut lexer = Lexer::new(source);
+    let (tokens, errors): (Vec<_>, Vec<_>) = lexer.by_ref().partition(|result| result.is_ok());
+    let warnings = lexer.take_warnings();
+
+    let tokens = if errors.is_empty() {
+        Ok(tokens
+            .into_iter()
+            .filter_map(|result| result.ok())
+            .collect())
+    } else {
+        Err(errors
+            .into_iter()
+            .filter_map(|result| result.err())
+            .collect())
+    };
+
+    (tokens, warnings)
+}
+
+/// Scans and parses all statements in `source`.
+///
+/// # Errors
+///
+/// Returns the first lexer or parser error encountered.
+pub fn parse(source: &str) -> Result<Vec<Stmt>, RloxError> {
+    let tokens = lex(source)?;
+    let statements = Parser::new(tokens).parse()?;
+
+    Ok(statements)
+}
+
+/// Scans, parses, and executes all statements in `source` with `interpreter`.
+///
+/// # Errors
+///
+/// Returns the first lexer, parser, or runtime error encountered. Execution stops at the first
+/// runtime error.
+pub fn run(source: &str, interpreter: &mut Interpreter) -> Result<(), RloxError> {
+    for stmt in parse(source)? {
+        interpreter.execute(&stmt)?;
+    }
+
+    Ok(())
+}
+
+/// Scans, parses, and executes `source` with a new [Interpreter], collecting every problem found
+/// as a [Diagnostic] instead of stopping at the first one. Statements that fail to parse are
+/// skipped while the rest still run, but nothing runs if `source` fails to scan.
+///
+/// Returns the value of the last statement if it is an expression statement that ran without
+/// errors, along with all diagnostics in the order they were found.
+pub fn run_diagnostics(source: &str) -> (Option<Value>, Vec<Diagnostic>) {
+    // The value of the final expression statement is returned, so it is not unused
+    let mut resolver = Resolver::new();
+    resolver.set_allow_final_expression(true);
+    let (statements, mut diagnostics) = static_diagnostics(source, resolver);
+
+    let mut interpreter = Interpreter::new();
+    let mut value = None;
+    for stmt in &statements {
+        value = match interpreter.execute_for_value(stmt) {
+            Ok(value) => value,
+            Err(error) => {
+                diagnostics.push(Diagnostic::from(&RloxError::from(error)));
+                None
+            }
+        };
+    }
+
+    (value, diagnostics)
+}
+
+/// Scans, parses, and resolves `source` without executing it, returning every lexer and parser
+/// error along with every lexer and resolver warning found as a [Diagnostic].
+pub fn check_diagnostics(source: &str) -> Vec<Diagnostic> {
+    static_diagnostics(source, Resolver::new()).1
+}
+
+/// Scans, parses, and resolves `source` with `resolver`. Returns the statements that parsed along
+/// with the errors found, followed by the lexer and resolver warnings.
+fn static_diagnostics(source: &str, resolver: Resolver) -> (Vec<Stmt>, Vec<Diagnostic>) {
+    let (statements, errors, warnings) = parse_all(source);
+    let mut diagnostics: Vec<Diagnostic> = errors.iter().map(Diagnostic::from).collect();
+    diagnostics.extend(warnings.into_iter().map(|warning| Diagnostic {
+        severity: Severity::Warning,
+        ..Diagnostic::from(&RloxError::from(warning))
+    }));
+    diagnostics.extend(resolver.resolve(&statements).iter().map(Diagnostic::from));
+
+    (statements, diagnostics)
+}
+
+/// Scans and parses `source` without executing it, returning every lexer and parser error found.
+/// Parser errors are only looked for if `source` scans without errors.
+pub fn check(source: &str) -> Vec<RloxError> {
+    parse_all(source).1
+}
+
+/// Scans and parses `source`, skipping statements that fail to parse. Returns the statements
+/// that parsed along with all errors found, or no statements and all lexer errors if `source`
+/// fails to scan. Lexer warnings are returned last either way.
+fn parse_all(source: &str) -> (Vec<Stmt>, Vec<RloxError>, Vec<LexerError>) {
+    let (tokens, warnings) = lex_with_warnings(source);
+    let tokens = match tokens {
+        Ok(tokens) => tokens,
+        Err(errors) => {
+            let errors = errors.into_iter().map(RloxError::from).collect();
+            return (Vec::new(), errors, warnings);
+        }
+    };
+
+    let (statements, parser_errors) = Parser::new(tokens).parse_all();
+
+    (
+        statements,
+        parser_errors.into_iter().map(RloxError::from).collect(),
+        warnings,
+    )
+}
+
+/// How long each phase of [run_timed] took
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PhaseTimings {
+    pub lex: Duration,
+    pub parse: Duration,
+    pub interpret: Duration,
+}
+
+/// Scans, parses, and interprets all statements in `source` with `interpreter`, measuring how
+/// long each phase takes. Unlike [run], statements are executed with [Interpreter::interpret],
+/// so runtime errors are sent to the interpreter's error reporters instead of being returned.
+///
+/// # Errors
+///
+/// Returns every lexer error encountered, or the first parser error if there are no lexer
+/// errors.
+pub fn run_timed(source: &str, interpreter: &mut Interpreter) -> Result<PhaseTimings, RloxErrors> {
+    let start = Instant::now();
+    let tokens = lex_all(source)?;
+    let lex = start.elapsed();
+
+    let start = Instant::now();
+    let statements = Parser::new(tokens).parse().map_err(RloxError::from)?;
+    let parse = start.elapsed();
+
+    let start = Instant::now();
+    interpreter.interpret(statements);
+    let interpret = start.elapsed();
+
+    Ok(PhaseTimings {
+        lex,
+        parse,
+        interpret,
+    })
+}
+
+#[cfg(test)]
+mod tests;
@@ -8,16 +8,20 @@ use std::{
 };
 
 use rlox::{
-    interpreter::{ErrorReporter, Interpreter},
-    lexer::{self, Lexer},
+    error::Severity,
+    interpreter::{error::RuntimeError, ErrorReporter, Interpreter},
+    parser::json,
+    render::ErrorRenderer,
+    repl::{MetaCommand, ReplBuffer, ReplInput},
 };
-use rlox::{lexer::token::Token, parser::Parser};
 
-struct StderrErrorReporter;
+struct StderrErrorReporter {
+    renderer: ErrorRenderer,
+}
 
 impl ErrorReporter for StderrErrorReporter {
-    fn report_err(&self, error: &rlox::interpreter::error::RuntimeError) {
-        eprintln!("{error}");
+    fn report_err(&self, error: &RuntimeError) {
+        eprintln!("{}", self.renderer.render(&error.clone().into()));
     }
 }
 
@@ -25,67 +29,155 @@ fn main() -> anyhow::Result<()> {
     // Skip the current exe name
     let args: Vec<String> = env::args().skip(1).collect();
 
-    let err_reporter: Box<dyn ErrorReporter> = Box::new(StderrErrorReporter);
+    let mut no_color = false;
+    let mut time = false;
+    let mut check = false;
+    let mut emit_json = false;
+    let mut scripts = Vec::new();
+    for arg in args {
+        match arg.as_str() {
+            "--no-color" => no_color = true,
+            "--time" => time = true,
+            "--check" => check = true,
+            "--emit-json" => emit_json = true,
+            _ if arg.starts_with("--") => print_usage_and_exit(),
+            _ => scripts.push(arg),
+        }
+    }
+
+    let renderer = if no_color {
+        ErrorRenderer::new(false)
+    } else {
+        ErrorRenderer::for_stderr()
+    };
+
+    // Only running a script is timed
+    if time && (check || emit_json || scripts.is_empty()) {
+        print_usage_and_exit();
+    }
 
-    match args.len() {
-        0 => run_prompt([err_reporter])?,
+    match scripts.len() {
+        // There is nothing to check without a script
+        0 if check || emit_json => print_usage_and_exit(),
+        0 => run_prompt(renderer)?,
         1 => {
-            let path = PathBuf::from_str(&args[0]).context("convert String to PathBuf")?;
-            run_file(path)?;
-        }
-        2.. => {
-            println!("Usage: rlox [script]");
-            process::exit(64);
+            let path = PathBuf::from_str(&scripts[0]).context("convert String to PathBuf")?;
+            if check {
+                check_file(path, renderer)?;
+            } else if emit_json {
+                emit_json_file(path, renderer)?;
+            } else {
+                run_file(path, renderer, time)?;
+            }
         }
+        2.. => print_usage_and_exit(),
     }
 
     Ok(())
 }
 
-fn run_file(path: PathBuf) -> anyhow::Result<()> {
+fn print_usage_and_exit() -> ! {
+    println!("Usage: rlox [--no-color] [--time] [--check] [--emit-json] [script]");
+    process::exit(64);
+}
+
+fn run_file(path: PathBuf, renderer: ErrorRenderer, time: bool) -> anyhow::Result<()> {
     let src_file = fs::read_to_string(path)?;
 
-    let err_reporter: Box<dyn ErrorReporter> = Box::new(StderrErrorReporter);
+    // Errors are reported by the run itself, with the same positions
+    rlox::check_diagnostics(src_file.as_str())
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Warning)
+        .for_each(|warning| eprintln!("{}", renderer.render_diagnostic(warning)));
+
+    let err_reporter: Box<dyn ErrorReporter> = Box::new(StderrErrorReporter { renderer });
     let mut interpreter = Interpreter::with_reporters([err_reporter]);
 
-    run(src_file.as_str(), &mut interpreter)?;
+    match rlox::run_timed(src_file.as_str(), &mut interpreter) {
+        Ok(timings) if time => {
+            eprintln!("lex:       {:?}", timings.lex);
+            eprintln!("parse:     {:?}", timings.parse);
+            eprintln!("interpret: {:?}", timings.interpret);
+        }
+        Ok(_) => {}
+        Err(errors) => {
+            for e in &errors.0 {
+                eprintln!("{}", renderer.render(e));
+            }
+            process::exit(65);
+        }
+    }
 
     Ok(())
 }
 
-fn run_prompt<I: IntoIterator<Item = Box<dyn ErrorReporter>>>(
-    err_reporter: I,
-) -> anyhow::Result<()> {
-    let mut interpreter = Interpreter::with_reporters(err_reporter);
+/// Reports every error and warning in the script at `path` without running it.
+fn check_file(path: PathBuf, renderer: ErrorRenderer) -> anyhow::Result<()> {
+    let src_file = fs::read_to_string(path)?;
+
+    let diagnostics = rlox::check_diagnostics(src_file.as_str());
+    for diagnostic in &diagnostics {
+        eprintln!("{}", renderer.render_diagnostic(diagnostic));
+    }
+
+    if diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == Severity::Error)
+    {
+        process::exit(65);
+    }
+
+    Ok(())
+}
+
+/// Prints the AST of the script at `path` as JSON without running it.
+fn emit_json_file(path: PathBuf, renderer: ErrorRenderer) -> anyhow::Result<()> {
+    let src_file = fs::read_to_string(path)?;
+
+    match rlox::parse(src_file.as_str()) {
+        Ok(statements) => println!("{}", json::to_json(&statements)),
+        Err(e) => {
+            eprintln!("{}", renderer.render(&e));
+            process::exit(65);
+        }
+    }
+
+    Ok(())
+}
+
+fn run_prompt(renderer: ErrorRenderer) -> anyhow::Result<()> {
+    let err_reporter: Box<dyn ErrorReporter> = Box::new(StderrErrorReporter { renderer });
+    let mut interpreter = Interpreter::with_reporters([err_reporter]);
+    let mut buffer = ReplBuffer::new();
 
     let prompt: &str = "> ";
+    let continuation_prompt: &str = "... ";
 
     print!("{}", prompt);
     stdout().lock().flush().context("flush stdout")?;
     for line in stdin().lines() {
         let line = line.context("read line from stdin")?;
 
-        if let Err(e) = run(line.as_str(), &mut interpreter) {
-            eprintln!("{e}");
+        if let Some(command) = MetaCommand::parse(line.as_str()) {
+            if command == MetaCommand::Quit {
+                break;
+            }
+            print!("{}", command.run(&mut interpreter, &mut buffer));
+        } else {
+            match buffer.push_line(line.as_str()) {
+                ReplInput::Complete(statements) => interpreter.interpret_repl(statements),
+                ReplInput::Incomplete => {}
+                ReplInput::Invalid(e) => eprintln!("{}", renderer.render(&e)),
+            }
         }
 
-        print!("{}", prompt);
+        if buffer.is_empty() {
+            print!("{}", prompt);
+        } else {
+            print!("{}", continuation_prompt);
+        }
         stdout().lock().flush().context("flush stdout")?;
     }
 
     Ok(())
 }
-
-fn run(source: &str, interpreter: &mut Interpreter) -> anyhow::Result<()> {
-    let lexer = Lexer::new(source);
-    let tokens = lexer
-        .scan_all_tokens()
-        .into_iter()
-        .collect::<lexer::Result<Vec<Token>>>()?;
-    let mut parser = Parser::new(tokens);
-    let statements = parser.parse()?;
-
-    interpreter.interpret(statements);
-
-    Ok(())
-}
@@ -17,16 +17,95 @@ pub enum ParserError {
     #[error("expected expression, got: {0}")]
     InvalidPrimaryExpressionToken(Token),
 
-    #[error("invalid assignment target at: {0}")]
+    #[error("invalid assignment target")]
     InvalidAssignmentTarget(Token),
 
-    // TODO: Try to include line and column info when reporting `MissingExpectedToken` error.
+    #[error("cannot assign to the literal '{}'", .0.token_type.name().to_lowercase())]
+    AssignToLiteral(Token),
+
     #[error("expected {}: {}",.token_type.name(), .message)]
     MissingExpectedToken {
         token_type: TokenType,
         message: String,
+        /// Line of the token found instead, or of the last token at the end of the input
+        line: usize,
+        /// Column of the token found instead, or of the last token at the end of the input
+        col: usize,
     },
 
     #[error("unexpected {}, expected {}", .got, .expected)]
     UnexpectedLanguageComponent { expected: String, got: String },
+
+    #[error(
+        "cannot use reserved keyword '{}' as a variable name",
+        .0.token_type.name().to_lowercase()
+    )]
+    KeywordAsVariableName(Token),
+
+    /// A token that none of several possible language components can start with, like a `}` at
+    /// the start of a statement.
+    #[error("unexpected {}, expected {}", .got, describe_expected(.expected))]
+    UnexpectedToken {
+        got: Token,
+        /// Descriptions of what could have appeared instead, like "a statement"
+        expected: Vec<&'static str>,
+    },
+
+    #[error("unexpected {0} after expression")]
+    UnexpectedTrailingToken(Token),
+
+    #[error("cannot use 'break' outside of a loop")]
+    BreakOutsideLoop(Token),
+
+    #[error("cannot use 'return' outside of a function")]
+    ReturnOutsideFunction(Token),
+
+    #[error("undefined loop label '{}'", .label)]
+    UndefinedLabel { label: String, token: Token },
+
+    #[error("code is nested too deeply")]
+    NestingTooDeep { line: usize, col: usize },
+
+    #[error("'else' without a matching 'if'")]
+    DanglingElse { line: usize, col: usize },
+
+    #[error(
+        "cannot use a {} declaration as the body of an 'if', 'else', or loop without a block",
+        .0.token_type.name().to_lowercase()
+    )]
+    DeclarationWithoutBlock(Token),
+}
+
+/// Lists `expected` as "a, b, or c".
+fn describe_expected(expected: &[&str]) -> String {
+    match expected {
+        [] => "nothing".to_owned(),
+        [only] => only.to_string(),
+        [first, second] => format!("{first} or {second}"),
+        [rest @ .., last] => format!("{}, or {last}", rest.join(", ")),
+    }
+}
+
+impl ParserError {
+    /// Returns the line and column in the source code where the error occurred, if known.
+    pub fn position(&self) -> Option<(usize, usize)> {
+        match self {
+            ParserError::InvalidTokenToOperatorConversion(token)
+            | ParserError::InvalidPrimaryExpressionToken(token)
+            | ParserError::InvalidAssignmentTarget(token)
+            | ParserError::AssignToLiteral(token)
+            | ParserError::KeywordAsVariableName(token)
+            | ParserError::UnexpectedTrailingToken(token)
+            | ParserError::BreakOutsideLoop(token)
+            | ParserError::ReturnOutsideFunction(token)
+            | ParserError::DeclarationWithoutBlock(token)
+            | ParserError::UnexpectedToken { got: token, .. }
+            | ParserError::UndefinedLabel { token, .. } => Some((token.line, token.col)),
+            ParserError::DanglingElse { line, col }
+            | ParserError::NestingTooDeep { line, col }
+            | ParserError::MissingExpectedToken { line, col, .. } => Some((*line, *col)),
+            ParserError::UnexpectedEndOfTokens
+            | ParserError::UnexpectedLanguageComponent { .. } => None,
+        }
+    }
 }
@@ -0,0 +1,218 @@
+use crate::lexer::token::{Token, TokenType};
+
+use super::types::{ExprId, ExprNode, ExprRef, Stmt, Value};
+
+/// Converts `statements` into a JSON array with one object per statement, for tools that consume
+/// the AST outside of Rust. Every statement and expression object has a `"kind"` field with the
+/// name of its variant.
+pub fn to_json(statements: &[Stmt]) -> String {
+    array(statements.iter().map(stmt))
+}
+
+fn stmt(stmt: &Stmt) -> String {
+    match stmt {
+        Stmt::Block(statements) => object(
+            "Block",
+            [("statements", array(statements.iter().map(self::stmt)))],
+        ),
+        Stmt::Break { keyword, label } => object(
+            "Break",
+            [
+                ("label", label.as_deref().map_or_else(null, string)),
+                ("line", keyword.line.to_string()),
+                ("col", keyword.col.to_string()),
+            ],
+        ),
+        Stmt::Const { name, initializer } => object(
+            "Const",
+            [
+                ("name", identifier(name)),
+                ("initializer", expr(initializer.root())),
+            ],
+        ),
+        Stmt::Expression(expression) => {
+            object("Expression", [("expression", expr(expression.root()))])
+        }
+        Stmt::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => object(
+            "If",
+            [
+                ("condition", expr(condition.root())),
+                ("then_branch", self::stmt(then_branch)),
+                (
+                    "else_branch",
+                    else_branch.as_deref().map_or_else(null, self::stmt),
+                ),
+            ],
+        ),
+        Stmt::Labeled { label, body } => object(
+            "Labeled",
+            [("label", string(label)), ("body", self::stmt(body))],
+        ),
+        Stmt::Print(expression) => object("Print", [("expression", expr(expression.root()))]),
+        Stmt::Var { name, initializer } => object(
+            "Var",
+            [
+                ("name", identifier(name)),
+                (
+                    "initializer",
+                    initializer
+                        .as_ref()
+                        .map_or_else(null, |initializer| expr(initializer.root())),
+                ),
+            ],
+        ),
+        Stmt::While { condition, body } => object(
+            "While",
+            [
+                ("condition", expr(condition.root())),
+                ("body", self::stmt(body)),
+            ],
+        ),
+    }
+}
+
+fn expr(expr: ExprRef<'_>) -> String {
+    let child = |id: &ExprId| self::expr(expr.child(*id));
+
+    match expr.node() {
+        ExprNode::Assign { name, value } => object(
+            "Assign",
+            [("name", identifier(name)), ("value", child(value))],
+        ),
+        ExprNode::Binary {
+            left,
+            operator,
+            right,
+        } => object(
+            "Binary",
+            [
+                ("operator", string(&operator.to_string())),
+                ("left", child(left)),
+                ("right", child(right)),
+            ],
+        ),
+        ExprNode::Call {
+            callee, arguments, ..
+        } => object(
+            "Call",
+            [
+                ("callee", child(callee)),
+                ("arguments", array(arguments.iter().map(child))),
+            ],
+        ),
+        ExprNode::Grouping { inner } => object("Grouping", [("expression", child(inner))]),
+        ExprNode::Index { object, index, .. } => self::object(
+            "Index",
+            [("object", child(object)), ("index", child(index))],
+        ),
+        ExprNode::Literal { value } => object("Literal", [("value", self::value(value))]),
+        ExprNode::Logical {
+            left,
+            operator,
+            right,
+        } => object(
+            "Logical",
+            [
+                ("operator", string(&operator.to_string())),
+                ("left", child(left)),
+                ("right", child(right)),
+            ],
+        ),
+        ExprNode::Unary { operator, right } => object(
+            "Unary",
+            [
+                ("operator", string(&operator.to_string())),
+                ("right", child(right)),
+            ],
+        ),
+        ExprNode::Variable { name } => object("Variable", [("name", identifier(name))]),
+    }
+}
+
+fn value(value: &Value) -> String {
+    match value {
+        Value::Boolean(boolean) => boolean.to_string(),
+        // JSON has no representation for infinity or NaN
+        Value::Number(number) if !number.is_finite() => null(),
+        Value::Number(number) => number.to_string(),
+        Value::String(str) => string(str),
+        Value::NativeFunction(_) => string(&value.to_string()),
+        Value::Nil => null(),
+    }
+}
+
+/// The name of an identifier token, or the name of the token type for any other token.
+fn identifier(token: &Token) -> String {
+    match token.token_type {
+        TokenType::Identifier(ref name) => string(name),
+        ref token_type => string(token_type.name()),
+    }
+}
+
+fn object<const N: usize>(kind: &str, fields: [(&str, String); N]) -> String {
+    let fields = fields
+        .iter()
+        .map(|(name, value)| format!(",{}:{value}", string(name)))
+        .collect::<String>();
+
+    format!("{{\"kind\":{}{fields}}}", string(kind))
+}
+
+fn array(elements: impl Iterator<Item = String>) -> String {
+    format!("[{}]", elements.collect::<Vec<_>>().join(","))
+}
+
+fn null() -> String {
+    "null".to_owned()
+}
+
+/// Quotes `str` as a JSON string, escaping it as needed.
+fn string(str: &str) -> String {
+    let mut json = String::with_capacity(str.len() + 2);
+    json.push('"');
+
+    for c in str.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_statements_to_json() {
+        let statements = crate::parse("var a = 1 + 2; if (a) print \"hi\"; else a = nil;").unwrap();
+
+        let expected = concat!(
+            r#"[{"kind":"Var","name":"a","initializer":{"kind":"Binary","operator":"+","#,
+            r#""left":{"kind":"Literal","value":1},"right":{"kind":"Literal","value":2}}},"#,
+            r#"{"kind":"If","condition":{"kind":"Variable","name":"a"},"#,
+            r#""then_branch":{"kind":"Print","expression":{"kind":"Literal","value":"hi"}},"#,
+            r#""else_branch":{"kind":"Expression","expression":{"kind":"Assign","name":"a","#,
+            r#""value":{"kind":"Literal","value":null}}}}]"#,
+        );
+
+        assert_eq!(to_json(&statements), expected);
+    }
+
+    #[test]
+    fn escapes_strings() {
+        assert_eq!(string("a \"b\" \\ c\n\u{1}"), r#""a \"b\" \\ c\n\u0001""#);
+    }
+}
@@ -1,20 +1,47 @@
-use crate::lexer::token::{
-    Token,
-    TokenType::{self, *},
+// `String` is the string literal token type from the glob import below
+use std::{collections::HashSet, rc::Rc, string::String as StdString};
+
+use crate::{
+    error::RloxError,
+    lexer::token::{
+        Token,
+        TokenType::{self, *},
+    },
 };
 
-use self::types::{Expr, Stmt, Value};
+use self::types::{Expr, ExprBuilder, ExprId, ExprNode, Stmt, Value};
 use self::{
     error::{ParserError, Result},
     types::Operator,
 };
 
 pub mod error;
+pub mod json;
+pub mod optimize;
 pub mod types;
+pub mod walk;
+
+/// Default for [Parser::set_max_nesting_depth], low enough to not overflow the stack of a thread
+/// with the default stack size
+const DEFAULT_MAX_NESTING_DEPTH: usize = 64;
 
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    /// Number of loops enclosing the statement currently being parsed
+    loop_depth: usize,
+    /// Labels of the loops enclosing the statement currently being parsed
+    loop_labels: Vec<StdString>,
+    /// Whether the final expression statement may omit its semicolon
+    lenient: bool,
+    /// Number of nested expressions enclosing the expression currently being parsed
+    nesting_depth: usize,
+    /// Deepest nesting of expressions allowed before giving up instead of overflowing the stack
+    max_nesting_depth: usize,
+    /// String literals seen so far, so that identical literals share the same storage
+    strings: HashSet<Rc<str>>,
+    /// Nodes of the expression currently being parsed
+    nodes: ExprBuilder,
 }
 
 impl Parser {
@@ -22,9 +49,30 @@ impl Parser {
         Self {
             tokens: tokens.into_iter().collect(),
             current: 0,
+            loop_depth: 0,
+            loop_labels: Vec::new(),
+            lenient: false,
+            nesting_depth: 0,
+            max_nesting_depth: DEFAULT_MAX_NESTING_DEPTH,
+            strings: HashSet::new(),
+            nodes: ExprBuilder::new(),
         }
     }
 
+    /// Sets how deeply code may be nested, counting parentheses, chained unary operators and
+    /// assignments, and blocks. Parsing fails with [ParserError::NestingTooDeep] past this depth.
+    pub fn set_max_nesting_depth(&mut self, max_nesting_depth: usize) {
+        self.max_nesting_depth = max_nesting_depth;
+    }
+
+    /// Enables or disables lenient mode. When enabled, an expression statement at the very end
+    /// of the input does not need a trailing semicolon, which is convenient in a REPL.
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.lenient = lenient;
+    }
+
+    /// Parses all statements, stopping at the first error. Empty input, including input that is
+    /// only an `Eof` token, parses into no statements.
     pub fn parse(&mut self) -> Result<Vec<Stmt>> {
         let mut statements = Vec::new();
         while !self.is_at_end() {
@@ -34,9 +82,76 @@ impl Parser {
         Ok(statements)
     }
 
+    /// Parses all statements like [Parser::parse], but instead of stopping at the first error,
+    /// skips ahead to the next statement and keeps parsing. Returns the statements that parsed
+    /// successfully along with every error encountered.
+    pub fn parse_all(&mut self) -> (Vec<Stmt>, Vec<ParserError>) {
+        let mut statements = Vec::new();
+        let mut errors = Vec::new();
+
+        loop {
+            match self.parse_one() {
+                Ok(Some(statement)) => statements.push(statement),
+                Ok(None) => break,
+                Err(error) => errors.push(error),
+            }
+        }
+
+        (statements, errors)
+    }
+
+    /// Parses exactly one top-level declaration, returning `None` once all tokens have been
+    /// consumed. On error, skips ahead to the next statement so that the following call picks up
+    /// where parsing can sensibly resume.
+    pub fn parse_one(&mut self) -> Result<Option<Stmt>> {
+        if self.is_at_end() {
+            return Ok(None);
+        }
+
+        match self.declaration() {
+            Ok(statement) => Ok(Some(statement)),
+            Err(error) => {
+                self.synchronize();
+                Err(error)
+            }
+        }
+    }
+
+    /// Scans and parses `source` as exactly one expression, without a trailing semicolon.
+    ///
+    /// Operators bind from loosest to tightest in the order: assignment (`=`, right associative),
+    /// nil coalescing (`??`), `or`, `and`, equality (`==`, `!=`), comparison (`<`, `<=`, `>`,
+    /// `>=`), term (`+`, `-`), factor (`*`, `/`, `%`), unary (`!`, `-`, `+`), and finally calls.
+    /// All binary operators other than assignment are left associative.
+    ///
+    /// ```
+    /// use rlox::parser::Parser;
+    ///
+    /// let expr = Parser::parse_expression("-2 * 3 + 4 == 1 / 2 - 3").unwrap();
+    ///
+    /// assert_eq!(expr.to_string(), "(== (+ (* (- 2) 3) 4) (- (/ 1 2) 3))");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the first lexer or parser error encountered, or
+    /// [ParserError::UnexpectedTrailingToken] if any tokens remain after the expression.
+    pub fn parse_expression(source: &str) -> std::result::Result<Expr, RloxError> {
+        let mut parser = Parser::new(crate::lex(source)?);
+        let expr = parser.expression()?;
+
+        if let Some(token) = parser.peek() {
+            return Err(ParserError::UnexpectedTrailingToken(token.clone()).into());
+        }
+
+        Ok(expr)
+    }
+
     fn declaration(&mut self) -> Result<Stmt> {
         if self.matches_any([Var]) {
-            self.var_declaration().inspect_err(|_| self.synchronize())
+            self.var_declaration()
+        } else if self.matches_any([Const]) {
+            self.const_declaration()
         } else {
             self.statement()
         }
@@ -44,13 +159,7 @@ impl Parser {
 
     fn var_declaration(&mut self) -> Result<Stmt> {
         self.consume(Var, "expected a 'var' keyword")?;
-
-        debug_assert!(self.peek().map(|token| token.is_identifier()) == Some(true));
-
-        // TODO: Fix this ugly little hack to get Identifiers to work.
-        // The PartialEq impl for TokenType should not be broken and ignore the
-        // value held by the variant.
-        let name = self.consume(Identifier("".to_owned()), "expected variable name")?;
+        let name = self.variable_name()?;
 
         let initializer = if self.matches_any([Equal]) {
             self.advance().ok_or(ParserError::UnexpectedEndOfTokens)?;
@@ -64,6 +173,34 @@ impl Parser {
         Ok(Stmt::Var { name, initializer })
     }
 
+    fn const_declaration(&mut self) -> Result<Stmt> {
+        self.consume(Const, "expected a 'const' keyword")?;
+        let name = self.variable_name()?;
+
+        self.consume(Equal, "expected '=' after constant name")?;
+        let initializer = self.expression()?;
+
+        self.consume(Semicolon, "expected ';' after constant declaration")?;
+
+        Ok(Stmt::Const { name, initializer })
+    }
+
+    /// Parses the name of a variable or constant being declared.
+    fn variable_name(&mut self) -> Result<Token> {
+        if let Some(token) = self.peek().filter(|token| token.is_keyword()) {
+            return Err(ParserError::KeywordAsVariableName(token.clone()));
+        }
+
+        // TODO: Fix this ugly little hack to get Identifiers to work.
+        // The PartialEq impl for TokenType should not be broken and ignore the
+        // value held by the variant.
+        self.consume(Identifier("".to_owned()), "expected variable name")
+    }
+
+    /// Parses any statement other than a declaration. Declarations are rejected here because the
+    /// bodies of `if`, `else`, and loops are parsed as statements, and only a [Stmt::Block]
+    /// creates a new scope: a braceless `while (cond) var x = 1;` would otherwise declare `x` in
+    /// the surrounding scope.
     fn statement(&mut self) -> Result<Stmt> {
         let current_token = self.peek().ok_or(ParserError::UnexpectedEndOfTokens)?;
         match current_token.token_type {
@@ -72,21 +209,133 @@ impl Parser {
             If => self.if_statement(),
             While => self.while_statement(),
             For => self.for_statement(),
+            Break => self.break_statement(),
+            // Functions are not supported yet, so every `return` is outside of one
+            Return => Err(ParserError::ReturnOutsideFunction(current_token.clone())),
+            Var | Const => Err(ParserError::DeclarationWithoutBlock(current_token.clone())),
+            // A matching `if` would already have consumed the `else` in `if_statement`
+            Else => Err(ParserError::DanglingElse {
+                line: current_token.line,
+                col: current_token.col,
+            }),
+            Identifier(_) if self.peek_next().map(|token| &token.token_type) == Some(&Colon) => {
+                self.labeled_statement()
+            }
+            ref token_type if !Self::starts_expression(token_type) => {
+                Err(ParserError::UnexpectedToken {
+                    got: current_token.clone(),
+                    expected: vec!["a statement", "an expression"],
+                })
+            }
             _ => self.expression_statement(),
         }
     }
 
-    fn block(&mut self) -> Result<Stmt> {
-        self.consume(LeftBrace, "expected '{' at start of block")?;
-        let mut statements = Vec::new();
+    /// Returns true if `token_type` can be the first token of an expression.
+    fn starts_expression(token_type: &TokenType) -> bool {
+        matches!(
+            token_type,
+            Nil | False
+                | True
+                | String(_)
+                | Number(_)
+                | LeftParen
+                | Identifier(_)
+                | Bang
+                | Minus
+                | Plus
+        )
+    }
 
-        while !self.matches_any([RightBrace]) && !self.is_at_end() {
-            statements.push(self.declaration()?);
+    /// Parses a loop preceded by a label, like `outer: while (true) { ... }`, or a block preceded
+    /// by a label, like `setup: { ... }`. Block labels only name the block's scope for tooling
+    /// and cannot be the target of a `break`.
+    fn labeled_statement(&mut self) -> Result<Stmt> {
+        let label_token = self.consume(Identifier("".to_owned()), "expected a label")?;
+        let Identifier(label) = label_token.token_type else {
+            unreachable!("consumed token should be an identifier");
+        };
+        self.consume(Colon, "expected ':' after label")?;
+
+        if self.matches_any([LeftBrace]) {
+            return Ok(Stmt::Labeled {
+                label,
+                body: Box::new(self.block()?),
+            });
+        }
+
+        if !self.matches_any([While, For]) {
+            return Err(ParserError::UnexpectedLanguageComponent {
+                expected: "a loop or block after label".to_owned(),
+                got: self
+                    .peek()
+                    .map(|token| token.token_type.name().to_owned())
+                    .unwrap_or_else(|| "end of tokens".to_owned()),
+            });
         }
 
-        self.consume(RightBrace, "expected '}' at end of block")?;
+        self.loop_labels.push(label.clone());
+        let body = self.statement();
+        self.loop_labels.pop();
+
+        Ok(Stmt::Labeled {
+            label,
+            body: Box::new(body?),
+        })
+    }
+
+    fn break_statement(&mut self) -> Result<Stmt> {
+        let keyword = self.consume(Break, "expected a 'break' keyword")?;
+
+        let label = if self.peek().is_some_and(Token::is_identifier) {
+            let label_token = self.advance().cloned().expect("expected Identifier token");
+            let Identifier(label) = label_token.token_type.clone() else {
+                unreachable!("advanced token should be an identifier");
+            };
+
+            if !self.loop_labels.contains(&label) {
+                return Err(ParserError::UndefinedLabel {
+                    label,
+                    token: label_token,
+                });
+            }
+
+            Some(label)
+        } else {
+            if self.loop_depth == 0 {
+                return Err(ParserError::BreakOutsideLoop(keyword));
+            }
+
+            None
+        };
+
+        self.consume(Semicolon, "expected ';' after break")?;
+
+        Ok(Stmt::Break { keyword, label })
+    }
+
+    /// Parses a loop body while keeping track of the loop depth for `break` statements.
+    fn loop_body(&mut self) -> Result<Stmt> {
+        self.loop_depth += 1;
+        let body = self.statement();
+        self.loop_depth -= 1;
+
+        body
+    }
+
+    fn block(&mut self) -> Result<Stmt> {
+        self.nested(|parser| {
+            parser.consume(LeftBrace, "expected '{' at start of block")?;
+            let mut statements = Vec::new();
+
+            while !parser.matches_any([RightBrace]) && !parser.is_at_end() {
+                statements.push(parser.declaration()?);
+            }
+
+            parser.consume(RightBrace, "expected '}' at end of block")?;
 
-        Ok(Stmt::Block(statements))
+            Ok(Stmt::Block(statements))
+        })
     }
 
     fn print_statement(&mut self) -> Result<Stmt> {
@@ -126,7 +375,7 @@ impl Parser {
         let condition = self.expression()?;
         self.consume(RightParen, "expected ')' after condition")?;
 
-        let body = Box::new(self.statement()?);
+        let body = Box::new(self.loop_body()?);
 
         Ok(Stmt::While { condition, body })
     }
@@ -134,9 +383,13 @@ impl Parser {
     /// Tries to parse out a for loop and desugers that for loop into a [Stmt::Block]
     /// containing the initializer part of the for loop declaration and a while loop ([Stmt::While]).
     ///
-    /// Syntax expected: for ( initializer:<Stmt::Var> ; condition<Expr> ; increment<Expr> ) body<Stmt::Block | Stmt::Expression | Stmt::Print>
+    /// Syntax expected: for ( initializer:<Stmt::Var> ; condition<Expr> ; increment<Expr> ) body<Stmt::Block | Stmt::Expression | Stmt::Print | Stmt::Break>
     ///
     /// The increment part of the for loop will be appended to the end of the loop's body.
+    ///
+    /// Statements have no position of their own, so the synthetic block and while loop do not
+    /// need one either: runtime errors in any part of the loop are reported at the tokens of the
+    /// expression that failed, which keep their original source positions.
     fn for_statement(&mut self) -> Result<Stmt> {
         self.consume(For, "expected a 'for' keyword")?;
         self.consume(LeftParen, "expected '(' after while")?;
@@ -155,9 +408,7 @@ impl Parser {
         let condition = if !self.matches_any([Semicolon]) {
             self.expression()?
         } else {
-            Expr::Literal {
-                value: Value::Boolean(true),
-            }
+            Expr::literal(Value::Boolean(true))
         };
         self.consume(Semicolon, "expected a ';' after loop condition")?;
 
@@ -170,7 +421,7 @@ impl Parser {
         self.consume(RightParen, "expected ')' after loop increment")?;
 
         // Parse out body
-        let body = self.statement()?;
+        let body = self.loop_body()?;
 
         // Insert increment at the end of the body if it exists
         let body = if let Some(increment) = increment {
@@ -179,16 +430,15 @@ impl Parser {
                     stmts.push(Stmt::Expression(increment));
                     Stmt::Block(stmts)
                 }
-                Stmt::Expression(_) | Stmt::Print(_) => {
+                Stmt::Expression(_) | Stmt::Print(_) | Stmt::Break { .. } => {
                     Stmt::Block(vec![body, Stmt::Expression(increment)])
                 }
-                _ => {
-                    return Err(ParserError::UnexpectedLanguageComponent {
-                        expected: "a block, a print statement, or an expression statement"
+                _ => return Err(ParserError::UnexpectedLanguageComponent {
+                    expected:
+                        "a block, a print statement, an expression statement, or a break statement"
                             .to_owned(),
-                        got: body.name().to_string(),
-                    })
-                }
+                    got: body.name().to_string(),
+                }),
             }
         } else {
             body
@@ -211,17 +461,48 @@ impl Parser {
 
     fn expression_statement(&mut self) -> Result<Stmt> {
         let expr = self.expression()?;
-        self.consume(Semicolon, "expected ';' after expression")?;
+
+        if !(self.lenient && self.is_at_end()) {
+            self.consume(Semicolon, "expected ';' after expression")?;
+        }
 
         Ok(Stmt::Expression(expr))
     }
 
+    /// Parses a whole expression, as opposed to [Parser::sub_expression] which parses one nested
+    /// inside the expression currently being parsed.
     fn expression(&mut self) -> Result<Expr> {
-        self.assignment()
+        // Drop any nodes left behind by an expression that failed to parse
+        self.nodes = ExprBuilder::new();
+
+        let root = self.sub_expression()?;
+        Ok(self.nodes.finish(root))
+    }
+
+    fn sub_expression(&mut self) -> Result<ExprId> {
+        self.nested(Self::assignment)
     }
 
-    fn assignment(&mut self) -> Result<Expr> {
-        let expr = self.equality()?;
+    /// Parses with `parse` one level of nesting deeper than the current one.
+    fn nested<T>(&mut self, parse: fn(&mut Self) -> Result<T>) -> Result<T> {
+        if self.nesting_depth >= self.max_nesting_depth {
+            let (line, col) = self.current_position();
+            return Err(ParserError::NestingTooDeep { line, col });
+        }
+
+        self.nesting_depth += 1;
+        let result = parse(self);
+        self.nesting_depth -= 1;
+
+        result
+    }
+
+    fn assignment(&mut self) -> Result<ExprId> {
+        let literal_keyword = self
+            .peek()
+            .filter(|token| matches!(token.token_type, True | False | Nil))
+            .cloned();
+        let expr = self.nil_coalesce()?;
 
         if self.matches_any([Equal]) {
             let equals_token = self
@@ -229,13 +510,19 @@ impl Parser {
                 .cloned()
                 .ok_or(ParserError::UnexpectedEndOfTokens)?;
 
-            let value = self.assignment()?;
+            let value = self.sub_expression()?;
 
-            if let Expr::Variable { name } = expr {
-                return Ok(Expr::Assign {
-                    name,
-                    value: Box::new(value),
-                });
+            // The variable becomes the assignment in place, so that it does not linger in the tree
+            if let ExprNode::Variable { name } = self.nodes.get(expr) {
+                let name = name.clone();
+                self.nodes.replace(expr, ExprNode::Assign { name, value });
+                return Ok(expr);
+            }
+
+            // A literal keyword reads like a variable name, so call it out specifically
+            if let (ExprNode::Literal { .. }, Some(token)) = (self.nodes.get(expr), literal_keyword)
+            {
+                return Err(ParserError::AssignToLiteral(token));
             }
 
             return Err(ParserError::InvalidAssignmentTarget(equals_token));
@@ -244,7 +531,73 @@ impl Parser {
         Ok(expr)
     }
 
-    fn equality(&mut self) -> Result<Expr> {
+    fn nil_coalesce(&mut self) -> Result<ExprId> {
+        let mut expr = self.logic_or()?;
+
+        while self.matches_any([QuestionQuestion]) {
+            let operator_token = self
+                .advance()
+                .cloned()
+                .ok_or(ParserError::UnexpectedEndOfTokens)?;
+
+            let operator = Operator::try_from(operator_token)?;
+
+            let right = self.logic_or()?;
+            expr = self.nodes.push(ExprNode::Logical {
+                left: expr,
+                operator,
+                right,
+            });
+        }
+
+        Ok(expr)
+    }
+
+    fn logic_or(&mut self) -> Result<ExprId> {
+        let mut expr = self.logic_and()?;
+
+        while self.matches_any([Or]) {
+            let operator_token = self
+                .advance()
+                .cloned()
+                .ok_or(ParserError::UnexpectedEndOfTokens)?;
+
+            let operator = Operator::try_from(operator_token)?;
+
+            let right = self.logic_and()?;
+            expr = self.nodes.push(ExprNode::Logical {
+                left: expr,
+                operator,
+                right,
+            });
+        }
+
+        Ok(expr)
+    }
+
+    fn logic_and(&mut self) -> Result<ExprId> {
+        let mut expr = self.equality()?;
+
+        while self.matches_any([And]) {
+            let operator_token = self
+                .advance()
+                .cloned()
+                .ok_or(ParserError::UnexpectedEndOfTokens)?;
+
+            let operator = Operator::try_from(operator_token)?;
+
+            let right = self.equality()?;
+            expr = self.nodes.push(ExprNode::Logical {
+                left: expr,
+                operator,
+                right,
+            });
+        }
+
+        Ok(expr)
+    }
+
+    fn equality(&mut self) -> Result<ExprId> {
         let mut expr = self.comparison()?;
 
         while self.matches_any([BangEqual, EqualEqual]) {
@@ -256,17 +609,17 @@ impl Parser {
             let operator = Operator::try_from(operator_token)?;
 
             let right = self.comparison()?;
-            expr = Expr::Binary {
-                left: Box::new(expr),
+            expr = self.nodes.push(ExprNode::Binary {
+                left: expr,
                 operator,
-                right: Box::new(right),
-            };
+                right,
+            });
         }
 
         Ok(expr)
     }
 
-    fn comparison(&mut self) -> Result<Expr> {
+    fn comparison(&mut self) -> Result<ExprId> {
         let mut expr = self.term()?;
 
         while self.matches_any([Less, LessEqual, Greater, GreaterEqual]) {
@@ -278,17 +631,17 @@ impl Parser {
             let operator = Operator::try_from(operator_token)?;
 
             let right = self.term()?;
-            expr = Expr::Binary {
-                left: Box::new(expr),
+            expr = self.nodes.push(ExprNode::Binary {
+                left: expr,
                 operator,
-                right: Box::new(right),
-            };
+                right,
+            });
         }
 
         Ok(expr)
     }
 
-    fn term(&mut self) -> Result<Expr> {
+    fn term(&mut self) -> Result<ExprId> {
         let mut expr = self.factor()?;
 
         while self.matches_any([Minus, Plus]) {
@@ -300,20 +653,20 @@ impl Parser {
             let operator = Operator::try_from(operator_token)?;
 
             let right = self.factor()?;
-            expr = Expr::Binary {
-                left: Box::new(expr),
+            expr = self.nodes.push(ExprNode::Binary {
+                left: expr,
                 operator,
-                right: Box::new(right),
-            };
+                right,
+            });
         }
 
         Ok(expr)
     }
 
-    fn factor(&mut self) -> Result<Expr> {
+    fn factor(&mut self) -> Result<ExprId> {
         let mut expr = self.unary()?;
 
-        while self.matches_any([Slash, Star]) {
+        while self.matches_any([Percent, Slash, Star]) {
             let operator_token = self
                 .advance()
                 .cloned()
@@ -322,68 +675,109 @@ impl Parser {
             let operator = Operator::try_from(operator_token)?;
 
             let right = self.unary()?;
-            expr = Expr::Binary {
-                left: Box::new(expr),
+            expr = self.nodes.push(ExprNode::Binary {
+                left: expr,
                 operator,
-                right: Box::new(right),
-            };
+                right,
+            });
         }
 
         Ok(expr)
     }
 
-    fn unary(&mut self) -> Result<Expr> {
-        if self.matches_any([Bang, Minus]) {
+    fn unary(&mut self) -> Result<ExprId> {
+        if self.matches_any([Bang, Minus, Plus]) {
             let operator_token = self
                 .advance()
                 .cloned()
                 .ok_or(ParserError::UnexpectedEndOfTokens)?;
 
             let operator = Operator::try_from(operator_token)?;
-            let right = self.primary()?;
+            let right = self.nested(Self::unary)?;
 
-            return Ok(Expr::Unary {
-                operator,
-                right: Box::new(right),
-            });
+            return Ok(self.nodes.push(ExprNode::Unary { operator, right }));
+        }
+
+        self.call()
+    }
+
+    fn call(&mut self) -> Result<ExprId> {
+        let mut expr = self.primary()?;
+
+        loop {
+            if self.matches_any([LeftParen]) {
+                self.advance().expect("expected LeftParen token");
+                expr = self.finish_call(expr)?;
+            } else if self.matches_any([LeftBracket]) {
+                self.advance().expect("expected LeftBracket token");
+                let index = self.sub_expression()?;
+                let bracket = self.consume(RightBracket, "expected ']' after index")?;
+
+                expr = self.nodes.push(ExprNode::Index {
+                    object: expr,
+                    bracket,
+                    index,
+                });
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    /// Parses the arguments of a call after its opening parenthesis. A trailing comma is allowed
+    /// after the last argument.
+    fn finish_call(&mut self, callee: ExprId) -> Result<ExprId> {
+        let mut arguments = Vec::new();
+
+        while !self.matches_any([RightParen]) {
+            arguments.push(self.sub_expression()?);
+
+            if !self.matches_any([Comma]) {
+                break;
+            }
+            self.advance().expect("expected Comma token");
         }
 
-        self.primary()
+        let paren = self.consume(RightParen, "expected ')' after arguments")?;
+
+        Ok(self.nodes.push(ExprNode::Call {
+            callee,
+            paren,
+            arguments,
+        }))
     }
 
-    fn primary(&mut self) -> Result<Expr> {
+    fn primary(&mut self) -> Result<ExprId> {
         let token = self
             .advance()
             .cloned()
             .ok_or(ParserError::UnexpectedEndOfTokens)?;
 
-        let expr = match token.token_type {
-            Nil => Expr::Literal { value: Value::Nil },
-            False => Expr::Literal {
+        let node = match token.token_type {
+            Nil => ExprNode::Literal { value: Value::Nil },
+            False => ExprNode::Literal {
                 value: Value::Boolean(false),
             },
-            True => Expr::Literal {
+            True => ExprNode::Literal {
                 value: Value::Boolean(true),
             },
-            String(str) => Expr::Literal {
-                value: Value::String(str),
+            String(str) => ExprNode::Literal {
+                value: Value::String(self.intern(str)),
             },
-            Number(num) => Expr::Literal {
+            Number(num) => ExprNode::Literal {
                 value: Value::Number(num),
             },
             LeftParen => {
-                let inner_expr = self.expression()?;
+                let inner = self.sub_expression()?;
                 self.consume(RightParen, "expected ')' after expression.")?;
-                Expr::Grouping {
-                    inner: Box::new(inner_expr),
-                }
+                ExprNode::Grouping { inner }
             }
-            Identifier(_) => Expr::Variable { name: token },
+            Identifier(_) => ExprNode::Variable { name: token },
 
             _ => return Err(ParserError::InvalidPrimaryExpressionToken(token)),
         };
 
-        Ok(expr)
+        Ok(self.nodes.push(node))
     }
 }
 
@@ -401,41 +795,74 @@ impl Parser {
             .any(|target_token_type| current_token.token_type == target_token_type)
     }
 
+    /// Consumes and returns the current token, or returns `None` without consuming anything if
+    /// there are no tokens left.
     fn advance(&mut self) -> Option<&Token> {
+        if self.is_at_end() {
+            return None;
+        }
+
         self.current += 1;
 
-        return self.previous();
+        self.previous()
     }
 
     fn previous(&self) -> Option<&Token> {
         self.tokens.get(self.current - 1)
     }
 
+    /// Returns whether all tokens have been consumed. An `Eof` token marks the end of the
+    /// tokens, so it and anything after it are never parsed.
     fn is_at_end(&self) -> bool {
-        self.current >= self.tokens.len()
+        self.peek().is_none()
     }
 
     fn peek(&self) -> Option<&Token> {
-        self.tokens.get(self.current)
+        self.tokens
+            .get(self.current)
+            .filter(|token| token.token_type != Eof)
     }
 
-    fn consume(&mut self, token_type: TokenType, error_message: &str) -> Result<Token> {
-        let missing_token_error = ParserError::MissingExpectedToken {
-            token_type: token_type.clone(),
-            message: error_message.to_owned(),
-        };
+    fn peek_next(&self) -> Option<&Token> {
+        self.peek()?;
 
-        let current_token = match self.peek() {
-            Some(token) => token,
-            None => return Err(missing_token_error),
-        };
+        self.tokens
+            .get(self.current + 1)
+            .filter(|token| token.token_type != Eof)
+    }
 
-        if current_token.token_type == token_type {
+    /// Returns the shared storage for the string literal `string`, creating it the first time
+    /// the literal is seen.
+    fn intern(&mut self, string: StdString) -> Rc<str> {
+        if let Some(interned) = self.strings.get(string.as_str()) {
+            return Rc::clone(interned);
+        }
+
+        let interned: Rc<str> = Rc::from(string);
+        self.strings.insert(Rc::clone(&interned));
+        interned
+    }
+
+    fn consume(&mut self, token_type: TokenType, error_message: &str) -> Result<Token> {
+        if self.matches_any([token_type.clone()]) {
             let current_token = self.advance().ok_or(ParserError::UnexpectedEndOfTokens)?;
-            Ok(current_token.clone())
-        } else {
-            Err(missing_token_error)
+            return Ok(current_token.clone());
         }
+
+        let (line, col) = self.current_position();
+        Err(ParserError::MissingExpectedToken {
+            token_type,
+            message: error_message.to_owned(),
+            line,
+            col,
+        })
+    }
+
+    /// Returns the position of the current token, or of the last token at the end of the input.
+    fn current_position(&self) -> (usize, usize) {
+        self.peek()
+            .or_else(|| self.tokens.last())
+            .map_or((0, 0), |token| (token.line, token.col))
     }
 
     /// Escape all tokens until the next class, function, variable declaration, or for, if , while,
@@ -451,9 +878,11 @@ impl Parser {
 
             let current = self.peek().expect("current token should exist");
             match current.token_type {
-                Class | Fun | Var | For | If | While | Print | Return => return,
+                Class | Const | Fun | Var | For | If | While | Print | Return | Break => return,
                 _ => {}
             }
+
+            self.advance();
         }
     }
 }
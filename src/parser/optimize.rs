@@ -0,0 +1,255 @@
+use super::types::{Expr, ExprBuilder, ExprId, ExprNode, ExprRef, OperatorType, Stmt, Value};
+
+/// Folds arithmetic and comparisons on number literals into a single literal, like `2 * 3 + 4`
+/// into `10`. Division by zero is never folded so that it is still evaluated at runtime, and
+/// neither is `==` or `!=`, whose result depends on [Interpreter::set_float_epsilon].
+///
+/// [Interpreter::set_float_epsilon]: crate::interpreter::Interpreter::set_float_epsilon
+pub fn fold(statements: Vec<Stmt>) -> Vec<Stmt> {
+    statements.into_iter().map(fold_stmt).collect()
+}
+
+fn fold_stmt(stmt: Stmt) -> Stmt {
+    match stmt {
+        Stmt::Block(stmts) => Stmt::Block(fold(stmts)),
+        Stmt::Break { .. } => stmt,
+        Stmt::Expression(expr) => Stmt::Expression(fold_expr(expr)),
+        Stmt::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => Stmt::If {
+            condition: fold_expr(condition),
+            then_branch: Box::new(fold_stmt(*then_branch)),
+            else_branch: else_branch.map(|else_branch| Box::new(fold_stmt(*else_branch))),
+        },
+        Stmt::Labeled { label, body } => Stmt::Labeled {
+            label,
+            body: Box::new(fold_stmt(*body)),
+        },
+        Stmt::Print(expr) => Stmt::Print(fold_expr(expr)),
+        Stmt::Const { name, initializer } => Stmt::Const {
+            name,
+            initializer: fold_expr(initializer),
+        },
+        Stmt::Var { name, initializer } => Stmt::Var {
+            name,
+            initializer: initializer.map(fold_expr),
+        },
+        Stmt::While { condition, body } => Stmt::While {
+            condition: fold_expr(condition),
+            body: Box::new(fold_stmt(*body)),
+        },
+    }
+}
+
+fn fold_expr(expr: Expr) -> Expr {
+    let mut builder = ExprBuilder::new();
+    let root = fold_node(expr.root(), &mut builder).into_id(&mut builder);
+
+    builder.finish(root)
+}
+
+/// A folded sub-expression. Literals are only added to the arena once it is clear that their
+/// parent cannot be folded into a literal too, so that folding leaves no unused nodes behind.
+enum Folded {
+    Literal(Value),
+    Node(ExprId),
+}
+
+impl Folded {
+    fn into_id(self, builder: &mut ExprBuilder) -> ExprId {
+        match self {
+            Folded::Literal(value) => builder.push(ExprNode::Literal { value }),
+            Folded::Node(id) => id,
+        }
+    }
+}
+
+/// Copies `expr` into `builder` with its sub-expressions folded.
+fn fold_node(expr: ExprRef<'_>, builder: &mut ExprBuilder) -> Folded {
+    let child = |id: &ExprId, builder: &mut ExprBuilder| {
+        fold_node(expr.child(*id), builder).into_id(builder)
+    };
+
+    let node = match expr.node() {
+        ExprNode::Assign { name, value } => ExprNode::Assign {
+            name: name.clone(),
+            value: child(value, builder),
+        },
+        ExprNode::Binary {
+            left,
+            operator,
+            right,
+        } => {
+            let left = fold_node(expr.child(*left), builder);
+            let right = fold_node(expr.child(*right), builder);
+
+            if let (Folded::Literal(Value::Number(lhs)), Folded::Literal(Value::Number(rhs))) =
+                (&left, &right)
+            {
+                if let Some(value) = fold_numbers(*lhs, &operator.operator_type, *rhs) {
+                    return Folded::Literal(value);
+                }
+            }
+
+            ExprNode::Binary {
+                left: left.into_id(builder),
+                operator: operator.clone(),
+                right: right.into_id(builder),
+            }
+        }
+        ExprNode::Call {
+            callee,
+            paren,
+            arguments,
+        } => ExprNode::Call {
+            callee: child(callee, builder),
+            paren: paren.clone(),
+            arguments: arguments
+                .iter()
+                .map(|argument| child(argument, builder))
+                .collect(),
+        },
+        ExprNode::Index {
+            object,
+            bracket,
+            index,
+        } => ExprNode::Index {
+            object: child(object, builder),
+            bracket: bracket.clone(),
+            index: child(index, builder),
+        },
+        ExprNode::Grouping { inner } => match fold_node(expr.child(*inner), builder) {
+            literal @ Folded::Literal(_) => return literal,
+            Folded::Node(inner) => ExprNode::Grouping { inner },
+        },
+        ExprNode::Literal { value } => return Folded::Literal(value.clone()),
+        ExprNode::Logical {
+            left,
+            operator,
+            right,
+        } => ExprNode::Logical {
+            left: child(left, builder),
+            operator: operator.clone(),
+            right: child(right, builder),
+        },
+        ExprNode::Unary { operator, right } => {
+            match (
+                &operator.operator_type,
+                fold_node(expr.child(*right), builder),
+            ) {
+                (OperatorType::Minus, Folded::Literal(Value::Number(number))) => {
+                    return Folded::Literal(Value::Number(-number))
+                }
+                (OperatorType::Plus, right @ Folded::Literal(Value::Number(_))) => return right,
+                (_, right) => ExprNode::Unary {
+                    operator: operator.clone(),
+                    right: right.into_id(builder),
+                },
+            }
+        }
+        ExprNode::Variable { .. } => expr.node().clone(),
+    };
+
+    Folded::Node(builder.push(node))
+}
+
+/// Applies `operator` to two numbers, returning `None` if the result should not be folded.
+fn fold_numbers(lhs: f64, operator: &OperatorType, rhs: f64) -> Option<Value> {
+    let value = match operator {
+        OperatorType::Minus => Value::Number(lhs - rhs),
+        OperatorType::Percent if rhs != 0.0 => Value::Number(lhs % rhs),
+        OperatorType::Plus => Value::Number(lhs + rhs),
+        OperatorType::Slash if rhs != 0.0 => Value::Number(lhs / rhs),
+        OperatorType::Star => Value::Number(lhs * rhs),
+        OperatorType::Greater => Value::Boolean(lhs > rhs),
+        OperatorType::GreaterEqual => Value::Boolean(lhs >= rhs),
+        OperatorType::Less => Value::Boolean(lhs < rhs),
+        OperatorType::LessEqual => Value::Boolean(lhs <= rhs),
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::interpreter::Interpreter;
+
+    use super::*;
+
+    #[test]
+    fn folds_number_arithmetic() {
+        let statements = fold(crate::parse("print 2 * 3 + 4; print -(1 + 2);").unwrap());
+
+        let expected = [
+            Stmt::Print(Expr::literal(Value::Number(10.0))),
+            Stmt::Print(Expr::literal(Value::Number(-3.0))),
+        ];
+
+        assert_eq!(statements, expected);
+    }
+
+    #[test]
+    fn folding_leaves_no_unused_nodes() {
+        let statements = fold(crate::parse("print -(1 + 2) * a;").unwrap());
+
+        let Stmt::Print(expr) = &statements[0] else {
+            panic!("expected a print statement");
+        };
+        assert_eq!(expr.to_string(), "(* -3 (var Identifier a))");
+        assert_eq!(expr.node_count(), 3);
+    }
+
+    #[test]
+    fn folds_nested_statements_and_comparisons() {
+        let statements = fold(crate::parse("while (1 < 2) { print 1 >= 1; }").unwrap());
+
+        assert_eq!(
+            statements[0].to_string(),
+            "(While true is true => { (print true); })"
+        );
+    }
+
+    #[test]
+    fn folding_equality_does_not_change_results_under_float_epsilon() {
+        let source = "var equal = 0.1 + 0.2 == 0.3; var not_equal = 0.1 + 0.2 != 0.3;";
+
+        for statements in [
+            crate::parse(source).unwrap(),
+            fold(crate::parse(source).unwrap()),
+        ] {
+            let mut interpreter = Interpreter::new();
+            interpreter.set_float_epsilon(Some(1e-9)).unwrap();
+
+            interpreter.interpret(statements);
+
+            assert_eq!(interpreter.get_global("equal"), Some(&Value::Boolean(true)));
+            assert_eq!(
+                interpreter.get_global("not_equal"),
+                Some(&Value::Boolean(false))
+            );
+        }
+    }
+
+    #[test]
+    fn does_not_fold_division_by_zero() {
+        let statements = fold(crate::parse("1 / 0;").unwrap());
+
+        assert_eq!(statements[0].to_string(), "(/ 1 0);");
+    }
+
+    #[test]
+    fn does_not_fold_non_number_operands() {
+        let statements = fold(crate::parse(r#"print "a" + 1; print a * 2;"#).unwrap());
+
+        assert_eq!(statements[0].to_string(), r#"(print (+ "a" 1));"#);
+        assert_eq!(
+            statements[1].to_string(),
+            "(print (* (var Identifier a) 2));"
+        );
+    }
+}
@@ -1,6 +1,9 @@
+use std::rc::Rc;
+
 use pretty_assertions::assert_eq;
 
 use crate::{
+    error::RloxError,
     lexer::{
         error::Result,
         token::{Token, TokenType},
@@ -10,7 +13,7 @@ use crate::{
 };
 
 use super::{
-    types::{Expr, Operator, OperatorType, Stmt, Value},
+    types::{Expr, ExprBuilder, ExprNode, Operator, OperatorType, Stmt, Value},
     Parser,
 };
 
@@ -37,38 +40,58 @@ fn can_parse_block_statement() {
 
     let statements = Parser::new(tokens).parse().unwrap();
     let expected = Stmt::Block(Vec::from_iter([
-        Stmt::Expression(Expr::Binary {
-            left: Box::new(Expr::Literal {
-                value: Value::Number(1.0),
-            }),
-            operator: Operator {
+        Stmt::Expression(Expr::binary(
+            Expr::literal(Value::Number(1.0)),
+            Operator {
                 operator_type: OperatorType::Plus,
                 src_line: 2,
                 src_col: 15,
             },
-            right: Box::new(Expr::Literal {
-                value: Value::Number(2.0),
-            }),
-        }),
-        Stmt::Expression(Expr::Binary {
-            left: Box::new(Expr::Literal {
-                value: Value::String("Hello, ".to_owned()),
-            }),
-            operator: Operator {
+            Expr::literal(Value::Number(2.0)),
+        )),
+        Stmt::Expression(Expr::binary(
+            Expr::literal(Value::String("Hello, ".into())),
+            Operator {
                 operator_type: OperatorType::Plus,
                 src_line: 3,
                 src_col: 23,
             },
-            right: Box::new(Expr::Literal {
-                value: Value::String("world!".to_owned()),
-            }),
-        }),
+            Expr::literal(Value::String("world!".into())),
+        )),
     ]));
     let expected = [expected];
 
     assert_eq!(statements, expected);
 }
 
+#[test]
+fn can_display_block_statement() {
+    let tokens = tokenize(r#"{ 1 + 2; print "hi"; }"#);
+
+    let statements = Parser::new(tokens).parse().unwrap();
+
+    assert_eq!(statements[0].to_string(), r#"{ (+ 1 2); (print "hi"); }"#);
+}
+
+#[test]
+fn can_display_nested_and_empty_block_statements() {
+    let tokens = tokenize("{ { } 1; }");
+
+    let statements = Parser::new(tokens).parse().unwrap();
+
+    assert_eq!(statements[0].to_string(), "{ { } 1; }");
+}
+
+#[test]
+fn can_clone_nested_block_statement() {
+    let tokens = tokenize("{ var a = 1; { print a; } }");
+
+    let statements = Parser::new(tokens).parse().unwrap();
+    let cloned = statements[0].clone();
+
+    assert_eq!(cloned, statements[0]);
+}
+
 #[test]
 fn error_on_missing_closing_brace_for_block_statement() {
     let tokens = tokenize("{ 1 + 2; 3 * 4;");
@@ -77,6 +100,8 @@ fn error_on_missing_closing_brace_for_block_statement() {
     let expected = Err(ParserError::MissingExpectedToken {
         token_type: TokenType::RightBrace,
         message: "expected '}' at end of block".to_owned(),
+        line: 1,
+        col: 15,
     });
 
     assert_eq!(result, expected);
@@ -87,13 +112,35 @@ fn error_on_stray_opening_brace() {
     let tokens = tokenize("1 + 2; 3 * 4; }");
 
     let result = Parser::new(tokens).parse();
-    let expected = Err(ParserError::InvalidPrimaryExpressionToken(Token {
-        token_type: TokenType::RightBrace,
-        line: 1,
-        col: 15,
-    }));
+    let expected = ParserError::UnexpectedToken {
+        got: Token {
+            token_type: TokenType::RightBrace,
+            line: 1,
+            col: 15,
+        },
+        expected: vec!["a statement", "an expression"],
+    };
 
-    assert_eq!(result, expected);
+    assert_eq!(
+        expected.to_string(),
+        "unexpected RightBrace, expected a statement or an expression"
+    );
+    assert_eq!(result, Err(expected));
+}
+
+#[test]
+fn error_on_malformed_statement_start() {
+    let sources = ["* 2;", "; print 1;", ") 1;", "else_ = 1; == 2;"];
+
+    for source in sources {
+        let tokens = tokenize(source);
+        let (_, errors) = Parser::new(tokens).parse_all();
+
+        let [ParserError::UnexpectedToken { expected, .. }] = errors.as_slice() else {
+            panic!("expected a single unexpected token error for {source}: {errors:?}");
+        };
+        assert_eq!(expected, &["a statement", "an expression"], "{source}");
+    }
 }
 
 #[test]
@@ -101,19 +148,15 @@ fn can_parse_expression_statement() {
     let tokens = tokenize("1 + 2;");
 
     let result = Parser::new(tokens).parse().unwrap();
-    let expected = [Stmt::Expression(Expr::Binary {
-        left: Box::new(Expr::Literal {
-            value: Value::Number(1.0),
-        }),
-        operator: Operator {
+    let expected = [Stmt::Expression(Expr::binary(
+        Expr::literal(Value::Number(1.0)),
+        Operator {
             operator_type: OperatorType::Plus,
             src_line: 1,
             src_col: 3,
         },
-        right: Box::new(Expr::Literal {
-            value: Value::Number(2.0),
-        }),
-    })];
+        Expr::literal(Value::Number(2.0)),
+    ))];
 
     assert_eq!(result, expected);
 }
@@ -126,6 +169,35 @@ fn error_on_missing_semicolon_for_expression_statement() {
     let expected = Err(ParserError::MissingExpectedToken {
         token_type: TokenType::Semicolon,
         message: "expected ';' after expression".to_owned(),
+        line: 1,
+        col: 5,
+    });
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn can_parse_final_expression_without_semicolon_in_lenient_mode() {
+    let mut parser = Parser::new(tokenize("1 + 2"));
+    parser.set_lenient(true);
+
+    let result = parser.parse().unwrap();
+
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].to_string(), "(+ 1 2);");
+}
+
+#[test]
+fn error_on_missing_semicolon_before_end_in_lenient_mode() {
+    let mut parser = Parser::new(tokenize("1 + 2 print 3;"));
+    parser.set_lenient(true);
+
+    let result = parser.parse();
+    let expected = Err(ParserError::MissingExpectedToken {
+        token_type: TokenType::Semicolon,
+        message: "expected ';' after expression".to_owned(),
+        line: 1,
+        col: 11,
     });
 
     assert_eq!(result, expected);
@@ -136,9 +208,9 @@ fn can_parse_print_statement() {
     let tokens = tokenize("print \"Hello, world!\";");
 
     let result = Parser::new(tokens).parse().unwrap();
-    let expected = [Stmt::Print(Expr::Literal {
-        value: Value::String("Hello, world!".to_owned()),
-    })];
+    let expected = [Stmt::Print(Expr::literal(Value::String(
+        "Hello, world!".into(),
+    )))];
 
     assert_eq!(result, expected);
 }
@@ -154,9 +226,7 @@ fn can_parse_var_statement_with_initializer() {
             line: 1,
             col: 5,
         },
-        initializer: Some(Expr::Literal {
-            value: Value::Number(1.0),
-        }),
+        initializer: Some(Expr::literal(Value::Number(1.0))),
     }];
 
     assert_eq!(result, expected);
@@ -179,48 +249,126 @@ fn can_parse_var_statement_without_initializer() {
     assert_eq!(result, expected);
 }
 
+#[test]
+fn can_parse_const_statement() {
+    let tokens = tokenize("const a = 1;");
+
+    let result = Parser::new(tokens).parse().unwrap();
+    let expected = [Stmt::Const {
+        name: Token {
+            token_type: TokenType::Identifier("a".to_owned()),
+            line: 1,
+            col: 7,
+        },
+        initializer: Expr::literal(Value::Number(1.0)),
+    }];
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn error_on_const_statement_without_initializer() {
+    let tokens = tokenize("const a;");
+
+    let result = Parser::new(tokens).parse();
+    let expected = Err(ParserError::MissingExpectedToken {
+        token_type: TokenType::Equal,
+        message: "expected '=' after constant name".to_owned(),
+        line: 1,
+        col: 8,
+    });
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn error_on_keyword_as_variable_name() {
+    let tokens = tokenize("var print = 1;");
+
+    let result = Parser::new(tokens).parse();
+    let expected = ParserError::KeywordAsVariableName(Token {
+        token_type: TokenType::Print,
+        line: 1,
+        col: 9,
+    });
+
+    assert_eq!(
+        expected.to_string(),
+        "cannot use reserved keyword 'print' as a variable name"
+    );
+    assert_eq!(result, Err(expected));
+}
+
 #[test]
 fn can_parse_assign_expression() {
     let tokens = tokenize("b = 21 / 7;");
 
     let result = Parser::new(tokens).parse().unwrap();
-    let expected = [Stmt::Expression(Expr::Assign {
-        name: Token {
+    let expected = [Stmt::Expression(Expr::assign(
+        Token {
             token_type: TokenType::Identifier("b".to_owned()),
             line: 1,
             col: 1,
         },
-        value: Box::new(Expr::Binary {
-            left: Box::new(Expr::Literal {
-                value: Value::Number(21.0),
-            }),
-            operator: Operator {
+        Expr::binary(
+            Expr::literal(Value::Number(21.0)),
+            Operator {
                 operator_type: OperatorType::Slash,
                 src_line: 1,
                 src_col: 8,
             },
-            right: Box::new(Expr::Literal {
-                value: Value::Number(7.0),
-            }),
-        }),
-    })];
+            Expr::literal(Value::Number(7.0)),
+        ),
+    ))];
 
     assert_eq!(result, expected);
 }
 
+#[test]
+fn error_on_assignment_to_literal_keyword() {
+    let sources = [
+        ("nil = 1;", "cannot assign to the literal 'nil'"),
+        ("true = 2;", "cannot assign to the literal 'true'"),
+        ("false = 3;", "cannot assign to the literal 'false'"),
+    ];
+
+    for (source, message) in sources {
+        let tokens = tokenize(source);
+
+        let result = Parser::new(tokens.clone()).parse();
+        let expected = ParserError::AssignToLiteral(tokens[0].clone());
+
+        assert_eq!(expected.to_string(), message);
+        assert_eq!(result, Err(expected), "{source}");
+    }
+
+    // Only a bare literal keyword gets the specific error
+    let tokens = tokenize("(nil) = 1;");
+    let result = Parser::new(tokens.clone()).parse();
+
+    assert_eq!(
+        result,
+        Err(ParserError::InvalidAssignmentTarget(tokens[3].clone()))
+    );
+}
+
 #[test]
 fn error_on_invalid_assignment_target() {
     // Should not be able to assign a value to an expression evaluating into a boolean
     let tokens = tokenize("false == true = 5;");
 
     let result = Parser::new(tokens).parse();
-    let expected = Err(ParserError::InvalidAssignmentTarget(Token {
+    let expected = ParserError::InvalidAssignmentTarget(Token {
         token_type: TokenType::Equal,
         line: 1,
         col: 15,
-    }));
+    });
 
-    assert_eq!(result, expected);
+    assert_eq!(result, Err(expected));
+    assert_eq!(
+        RloxError::from(result.unwrap_err()).to_string(),
+        "[line 1: col 15] invalid assignment target"
+    );
 }
 
 #[test]
@@ -230,25 +378,54 @@ fn can_parse_binary_expression() {
     let mut parser = Parser::new(tokens);
     let result = parser.expression();
 
-    let expected = Ok(Expr::Binary {
-        left: Box::new(Expr::Literal {
-            value: Value::Number(21.0),
-        }),
-        operator: Operator {
+    let expected = Ok(Expr::binary(
+        Expr::literal(Value::Number(21.0)),
+        Operator {
             operator_type: OperatorType::Slash,
             src_line: 1,
             src_col: 4,
         },
-        right: Box::new(Expr::Literal {
-            value: Value::Number(7.0),
-        }),
-    });
+        Expr::literal(Value::Number(7.0)),
+    ));
 
     assert_eq!(result, expected);
     // Ensure all tokens were consumed
     assert!(parser.is_at_end());
 }
 
+#[test]
+fn can_parse_nil_coalesce_expression() {
+    let tokens = tokenize("a ?? 1 == 2");
+
+    let mut parser = Parser::new(tokens);
+    let expression = parser.expression().unwrap();
+
+    // `??` binds looser than equality
+    let expected = Expr::logical(
+        Expr::variable(Token {
+            token_type: TokenType::Identifier("a".to_owned()),
+            line: 1,
+            col: 1,
+        }),
+        Operator {
+            operator_type: OperatorType::QuestionQuestion,
+            src_line: 1,
+            src_col: 4,
+        },
+        Expr::binary(
+            Expr::literal(Value::Number(1.0)),
+            Operator {
+                operator_type: OperatorType::EqualEqual,
+                src_line: 1,
+                src_col: 9,
+            },
+            Expr::literal(Value::Number(2.0)),
+        ),
+    );
+
+    assert_eq!(expression, expected);
+}
+
 #[test]
 fn can_parse_grouping_expression() {
     let tokens = tokenize("((1 + 1))");
@@ -256,23 +433,15 @@ fn can_parse_grouping_expression() {
     let mut parser = Parser::new(tokens);
     let result = parser.expression();
 
-    let expected = Ok(Expr::Grouping {
-        inner: Box::new(Expr::Grouping {
-            inner: Box::new(Expr::Binary {
-                left: Box::new(Expr::Literal {
-                    value: Value::Number(1.0),
-                }),
-                operator: Operator {
-                    operator_type: OperatorType::Plus,
-                    src_line: 1,
-                    src_col: 5,
-                },
-                right: Box::new(Expr::Literal {
-                    value: Value::Number(1.0),
-                }),
-            }),
-        }),
-    });
+    let expected = Ok(Expr::grouping(Expr::grouping(Expr::binary(
+        Expr::literal(Value::Number(1.0)),
+        Operator {
+            operator_type: OperatorType::Plus,
+            src_line: 1,
+            src_col: 5,
+        },
+        Expr::literal(Value::Number(1.0)),
+    ))));
 
     assert_eq!(result, expected);
     // Ensure all tokens were consumed
@@ -289,6 +458,8 @@ fn error_on_unclosed_group_expression() {
     let expected = Err(ParserError::MissingExpectedToken {
         token_type: TokenType::RightParen,
         message: "expected ')' after expression.".to_owned(),
+        line: 1,
+        col: 8,
     });
 
     assert_eq!(result, expected);
@@ -309,17 +480,45 @@ fn can_parse_literal_expression() {
         .collect::<Vec<Expr>>();
 
     let expected = [
-        Expr::Literal {
-            value: Value::String("Hello, world!".to_owned()),
-        },
-        Expr::Literal {
-            value: Value::Number(1.0),
-        },
+        Expr::literal(Value::String("Hello, world!".into())),
+        Expr::literal(Value::Number(1.0)),
     ];
 
     assert_eq!(result, expected);
 }
 
+#[test]
+fn identical_string_literals_share_storage() {
+    let tokens = tokenize(r#""same" + "same" + "other";"#);
+
+    let statements = Parser::new(tokens).parse().unwrap();
+    let Stmt::Expression(expr) = &statements[0] else {
+        panic!("expected an expression statement");
+    };
+    let root = expr.root();
+    let ExprNode::Binary { left, right, .. } = root.node() else {
+        panic!("expected a binary expression");
+    };
+    let ExprNode::Binary {
+        left: first,
+        right: second,
+        ..
+    } = root.child(*left).node()
+    else {
+        panic!("expected a nested binary expression");
+    };
+
+    let strings = [first, second, right].map(|id| match root.child(*id).node() {
+        ExprNode::Literal {
+            value: Value::String(string),
+        } => Rc::clone(string),
+        _ => panic!("expected a string literal"),
+    });
+
+    assert!(Rc::ptr_eq(&strings[0], &strings[1]));
+    assert!(!Rc::ptr_eq(&strings[0], &strings[2]));
+}
+
 #[test]
 fn can_parse_unary_expression() {
     let tokens = [tokenize("-1"), tokenize("!true"), tokenize("!(1 <= 2)")];
@@ -335,48 +534,38 @@ fn can_parse_unary_expression() {
         .collect::<Vec<Expr>>();
 
     let expected = [
-        Expr::Unary {
-            operator: Operator {
+        Expr::unary(
+            Operator {
                 operator_type: OperatorType::Minus,
                 src_line: 1,
                 src_col: 1,
             },
-            right: Box::new(Expr::Literal {
-                value: Value::Number(1.0),
-            }),
-        },
-        Expr::Unary {
-            operator: Operator {
+            Expr::literal(Value::Number(1.0)),
+        ),
+        Expr::unary(
+            Operator {
                 operator_type: OperatorType::Bang,
                 src_line: 1,
                 src_col: 1,
             },
-            right: Box::new(Expr::Literal {
-                value: Value::Boolean(true),
-            }),
-        },
-        Expr::Unary {
-            operator: Operator {
+            Expr::literal(Value::Boolean(true)),
+        ),
+        Expr::unary(
+            Operator {
                 operator_type: OperatorType::Bang,
                 src_line: 1,
                 src_col: 1,
             },
-            right: Box::new(Expr::Grouping {
-                inner: Box::new(Expr::Binary {
-                    left: Box::new(Expr::Literal {
-                        value: Value::Number(1.0),
-                    }),
-                    operator: Operator {
-                        operator_type: OperatorType::LessEqual,
-                        src_line: 1,
-                        src_col: 6,
-                    },
-                    right: Box::new(Expr::Literal {
-                        value: Value::Number(2.0),
-                    }),
-                }),
-            }),
-        },
+            Expr::grouping(Expr::binary(
+                Expr::literal(Value::Number(1.0)),
+                Operator {
+                    operator_type: OperatorType::LessEqual,
+                    src_line: 1,
+                    src_col: 6,
+                },
+                Expr::literal(Value::Number(2.0)),
+            )),
+        ),
     ];
 
     assert_eq!(result, expected);
@@ -389,17 +578,123 @@ fn can_parse_variable_expression() {
     let mut parser = Parser::new(tokens);
     let expression = parser.expression().unwrap();
 
-    let expected = Expr::Variable {
-        name: Token {
-            token_type: TokenType::Identifier("a".to_owned()),
+    let expected = Expr::variable(Token {
+        token_type: TokenType::Identifier("a".to_owned()),
+        line: 1,
+        col: 1,
+    });
+
+    assert_eq!(expression, expected);
+}
+
+#[test]
+fn can_parse_index_expression() {
+    let tokens = tokenize("s[0]");
+
+    let mut parser = Parser::new(tokens);
+    let expression = parser.expression().unwrap();
+
+    let expected = Expr::index(
+        Expr::variable(Token {
+            token_type: TokenType::Identifier("s".to_owned()),
             line: 1,
             col: 1,
+        }),
+        Token {
+            token_type: TokenType::RightBracket,
+            line: 1,
+            col: 4,
         },
-    };
+        Expr::literal(Value::Number(0.0)),
+    );
 
     assert_eq!(expression, expected);
 }
 
+#[test]
+fn can_parse_chained_index_and_call_expressions() {
+    let tokens = tokenize("f(1)[0][1]");
+
+    let mut parser = Parser::new(tokens);
+    let expression = parser.expression().unwrap();
+
+    assert_eq!(
+        expression.to_string(),
+        "(index (index (call (var Identifier f) 1) 0) 1)"
+    );
+}
+
+#[test]
+fn error_on_unclosed_index() {
+    let tokens = tokenize("s[0;");
+
+    let mut parser = Parser::new(tokens);
+    let result = parser.expression();
+    let expected = Err(ParserError::MissingExpectedToken {
+        token_type: TokenType::RightBracket,
+        message: "expected ']' after index".to_owned(),
+        line: 1,
+        col: 4,
+    });
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn can_parse_call_expression() {
+    let tokens = tokenize("concat(1, 2)");
+
+    let mut parser = Parser::new(tokens);
+    let expression = parser.expression().unwrap();
+
+    let expected = Expr::call(
+        Expr::variable(Token {
+            token_type: TokenType::Identifier("concat".to_owned()),
+            line: 1,
+            col: 6,
+        }),
+        Token {
+            token_type: TokenType::RightParen,
+            line: 1,
+            col: 12,
+        },
+        vec![
+            Expr::literal(Value::Number(1.0)),
+            Expr::literal(Value::Number(2.0)),
+        ],
+    );
+
+    assert_eq!(expression, expected);
+}
+
+#[test]
+fn can_parse_call_expression_with_trailing_comma() {
+    let tokens = tokenize("f(a, b,)");
+
+    let mut parser = Parser::new(tokens);
+    let expression = parser.expression().unwrap();
+
+    let ExprNode::Call { arguments, .. } = expression.root().node() else {
+        panic!("expected a call expression");
+    };
+    assert_eq!(arguments.len(), 2);
+}
+
+#[test]
+fn error_on_call_expression_with_leading_comma() {
+    let tokens = tokenize("f(,a)");
+
+    let mut parser = Parser::new(tokens);
+    let result = parser.expression();
+    let expected = Err(ParserError::InvalidPrimaryExpressionToken(Token {
+        token_type: TokenType::Comma,
+        line: 1,
+        col: 3,
+    }));
+
+    assert_eq!(result, expected);
+}
+
 #[test]
 fn can_parse_if_statements_with_block_body() {
     let source = "if (condition) { print 1; print 2; }";
@@ -408,20 +703,14 @@ fn can_parse_if_statements_with_block_body() {
     let stmts = parser.parse().unwrap();
 
     let expected = Stmt::If {
-        condition: Expr::Variable {
-            name: Token {
-                token_type: TokenType::Identifier("condition".to_owned()),
-                line: 1,
-                col: 13,
-            },
-        },
+        condition: Expr::variable(Token {
+            token_type: TokenType::Identifier("condition".to_owned()),
+            line: 1,
+            col: 13,
+        }),
         then_branch: Box::new(Stmt::Block(vec![
-            Stmt::Print(Expr::Literal {
-                value: Value::Number(1.0),
-            }),
-            Stmt::Print(Expr::Literal {
-                value: Value::Number(2.0),
-            }),
+            Stmt::Print(Expr::literal(Value::Number(1.0))),
+            Stmt::Print(Expr::literal(Value::Number(2.0))),
         ])),
         else_branch: None,
     };
@@ -439,36 +728,28 @@ fn can_parse_if_statements_with_single_statement_body() {
 
     let expected = vec![
         Stmt::If {
-            condition: Expr::Variable {
-                name: Token {
-                    token_type: TokenType::Identifier("condition".to_owned()),
-                    line: 1,
-                    col: 13,
-                },
-            },
-            then_branch: Box::new(Stmt::Print(Expr::Literal {
-                value: Value::Number(1.0),
-            })),
+            condition: Expr::variable(Token {
+                token_type: TokenType::Identifier("condition".to_owned()),
+                line: 1,
+                col: 13,
+            }),
+            then_branch: Box::new(Stmt::Print(Expr::literal(Value::Number(1.0)))),
             else_branch: None,
         },
         Stmt::If {
-            condition: Expr::Variable {
-                name: Token {
-                    token_type: TokenType::Identifier("condition".to_owned()),
-                    line: 1,
-                    col: 37,
-                },
-            },
-            then_branch: Box::new(Stmt::Expression(Expr::Assign {
-                name: Token {
+            condition: Expr::variable(Token {
+                token_type: TokenType::Identifier("condition".to_owned()),
+                line: 1,
+                col: 37,
+            }),
+            then_branch: Box::new(Stmt::Expression(Expr::assign(
+                Token {
                     token_type: TokenType::Identifier("i".to_owned()),
                     line: 1,
                     col: 40,
                 },
-                value: Box::new(Expr::Literal {
-                    value: Value::Number(2.0),
-                }),
-            })),
+                Expr::literal(Value::Number(2.0)),
+            ))),
             else_branch: None,
         },
     ];
@@ -484,32 +765,24 @@ fn can_parse_nested_if_statements() {
     let stmts = parser.parse().unwrap();
 
     let expected = Stmt::If {
-        condition: Expr::Variable {
-            name: Token {
-                token_type: TokenType::Identifier("condition1".to_owned()),
-                line: 1,
-                col: 14,
-            },
-        },
+        condition: Expr::variable(Token {
+            token_type: TokenType::Identifier("condition1".to_owned()),
+            line: 1,
+            col: 14,
+        }),
         then_branch: Box::new(Stmt::Block(vec![Stmt::If {
-            condition: Expr::Variable {
-                name: Token {
-                    token_type: TokenType::Identifier("condition2".to_owned()),
-                    line: 1,
-                    col: 32,
-                },
-            },
+            condition: Expr::variable(Token {
+                token_type: TokenType::Identifier("condition2".to_owned()),
+                line: 1,
+                col: 32,
+            }),
             then_branch: Box::new(Stmt::Block(vec![Stmt::If {
-                condition: Expr::Variable {
-                    name: Token {
-                        token_type: TokenType::Identifier("condition3".to_owned()),
-                        line: 1,
-                        col: 50,
-                    },
-                },
-                then_branch: Box::new(Stmt::Print(Expr::Literal {
-                    value: Value::Number(1.0),
-                })),
+                condition: Expr::variable(Token {
+                    token_type: TokenType::Identifier("condition3".to_owned()),
+                    line: 1,
+                    col: 50,
+                }),
+                then_branch: Box::new(Stmt::Print(Expr::literal(Value::Number(1.0)))),
                 else_branch: None,
             }])),
             else_branch: None,
@@ -538,41 +811,31 @@ fn can_parse_else_if_statements() {
     let stmts = parser.parse().unwrap();
 
     let expected = Stmt::If {
-        condition: Expr::Variable {
-            name: Token {
-                token_type: TokenType::Identifier("condition1".to_owned()),
-                line: 2,
-                col: 18,
-            },
-        },
-        then_branch: Box::new(Stmt::Block(vec![Stmt::Print(Expr::Literal {
-            value: Value::Number(1.0),
-        })])),
+        condition: Expr::variable(Token {
+            token_type: TokenType::Identifier("condition1".to_owned()),
+            line: 2,
+            col: 18,
+        }),
+        then_branch: Box::new(Stmt::Block(vec![Stmt::Print(Expr::literal(
+            Value::Number(1.0),
+        ))])),
         else_branch: Some(Box::new(Stmt::If {
-            condition: Expr::Variable {
-                name: Token {
-                    token_type: TokenType::Identifier("condition2".to_owned()),
-                    line: 4,
-                    col: 25,
-                },
-            },
-            then_branch: Box::new(Stmt::Block(vec![Stmt::Print(Expr::Literal {
-                value: Value::Number(2.0),
-            })])),
+            condition: Expr::variable(Token {
+                token_type: TokenType::Identifier("condition2".to_owned()),
+                line: 4,
+                col: 25,
+            }),
+            then_branch: Box::new(Stmt::Block(vec![Stmt::Print(Expr::literal(
+                Value::Number(2.0),
+            ))])),
             else_branch: Some(Box::new(Stmt::If {
-                condition: Expr::Variable {
-                    name: Token {
-                        token_type: TokenType::Identifier("condition3".to_owned()),
-                        line: 6,
-                        col: 25,
-                    },
-                },
-                then_branch: Box::new(Stmt::Print(Expr::Literal {
-                    value: Value::Number(3.0),
-                })),
-                else_branch: Some(Box::new(Stmt::Print(Expr::Literal {
-                    value: Value::Number(4.0),
-                }))),
+                condition: Expr::variable(Token {
+                    token_type: TokenType::Identifier("condition3".to_owned()),
+                    line: 6,
+                    col: 25,
+                }),
+                then_branch: Box::new(Stmt::Print(Expr::literal(Value::Number(3.0)))),
+                else_branch: Some(Box::new(Stmt::Print(Expr::literal(Value::Number(4.0))))),
             })),
         })),
     };
@@ -581,6 +844,61 @@ fn can_parse_else_if_statements() {
     assert_eq!(stmts[0], expected);
 }
 
+#[test]
+fn error_on_else_without_if() {
+    let tokens = tokenize("else print 1;");
+
+    let result = Parser::new(tokens).parse();
+    let expected = ParserError::DanglingElse { line: 1, col: 4 };
+
+    assert_eq!(expected.to_string(), "'else' without a matching 'if'");
+    assert_eq!(result, Err(expected));
+}
+
+#[test]
+fn error_on_declaration_as_body_without_block() {
+    let sources = [
+        "if (true) var x = 1;",
+        "if (false) print 1; else var x = 1;",
+        "while (false) var x = 1;",
+        "for (;;) const x = 1;",
+        "outer: while (false) const x = 1;",
+    ];
+
+    for source in sources {
+        let tokens = tokenize(source);
+        let declaration = tokens
+            .iter()
+            .find(|token| matches!(token.token_type, TokenType::Var | TokenType::Const))
+            .unwrap()
+            .clone();
+
+        let result = Parser::new(tokens.clone()).parse();
+        let expected = ParserError::DeclarationWithoutBlock(declaration);
+
+        assert_eq!(result, Err(expected), "{source}");
+    }
+
+    assert_eq!(
+        ParserError::DeclarationWithoutBlock(tokenize("var")[0].clone()).to_string(),
+        "cannot use a var declaration as the body of an 'if', 'else', or loop without a block"
+    );
+}
+
+#[test]
+fn error_on_return_outside_function() {
+    let tokens = tokenize("return 1;");
+
+    let result = Parser::new(tokens.clone()).parse();
+    let expected = ParserError::ReturnOutsideFunction(tokens[0].clone());
+
+    assert_eq!(
+        expected.to_string(),
+        "cannot use 'return' outside of a function"
+    );
+    assert_eq!(result, Err(expected));
+}
+
 #[test]
 fn can_parse_while_loop_with_block_body() {
     let source = r#"while (true) { print 1; }"#;
@@ -589,12 +907,10 @@ fn can_parse_while_loop_with_block_body() {
     let stmts = parser.parse().unwrap();
 
     let expected = Stmt::While {
-        condition: Expr::Literal {
-            value: Value::Boolean(true),
-        },
-        body: Box::new(Stmt::Block(vec![Stmt::Print(Expr::Literal {
-            value: Value::Number(1.0),
-        })])),
+        condition: Expr::literal(Value::Boolean(true)),
+        body: Box::new(Stmt::Block(vec![Stmt::Print(Expr::literal(
+            Value::Number(1.0),
+        ))])),
     };
 
     assert_eq!(stmts.len(), 1);
@@ -609,12 +925,8 @@ fn can_parse_while_loop_with_single_statement_body() {
     let stmts = parser.parse().unwrap();
 
     let expected = Stmt::While {
-        condition: Expr::Literal {
-            value: Value::Boolean(true),
-        },
-        body: Box::new(Stmt::Print(Expr::Literal {
-            value: Value::Number(1.0),
-        })),
+        condition: Expr::literal(Value::Boolean(true)),
+        body: Box::new(Stmt::Print(Expr::literal(Value::Number(1.0)))),
     };
 
     assert_eq!(stmts.len(), 1);
@@ -635,60 +947,48 @@ fn can_parse_for_loop_with_block_body() {
                 line: 1,
                 col: 10,
             },
-            initializer: Some(Expr::Literal {
-                value: Value::Number(0.0),
-            }),
+            initializer: Some(Expr::literal(Value::Number(0.0))),
         },
         Stmt::While {
-            condition: Expr::Binary {
-                left: Box::new(Expr::Variable {
-                    name: Token {
-                        token_type: TokenType::Identifier("i".to_owned()),
-                        line: 1,
-                        col: 17,
-                    },
+            condition: Expr::binary(
+                Expr::variable(Token {
+                    token_type: TokenType::Identifier("i".to_owned()),
+                    line: 1,
+                    col: 17,
                 }),
-                operator: Operator {
+                Operator {
                     operator_type: OperatorType::Less,
                     src_line: 1,
                     src_col: 19,
                 },
-                right: Box::new(Expr::Literal {
-                    value: Value::Number(10.0),
-                }),
-            },
+                Expr::literal(Value::Number(10.0)),
+            ),
             body: Box::new(Stmt::Block(vec![
-                Stmt::Print(Expr::Variable {
-                    name: Token {
-                        token_type: TokenType::Identifier("i".to_owned()),
-                        line: 1,
-                        col: 44,
-                    },
-                }),
-                Stmt::Expression(Expr::Assign {
-                    name: Token {
+                Stmt::Print(Expr::variable(Token {
+                    token_type: TokenType::Identifier("i".to_owned()),
+                    line: 1,
+                    col: 44,
+                })),
+                Stmt::Expression(Expr::assign(
+                    Token {
                         token_type: TokenType::Identifier("i".to_owned()),
                         line: 1,
                         col: 25,
                     },
-                    value: Box::new(Expr::Binary {
-                        left: Box::new(Expr::Variable {
-                            name: Token {
-                                token_type: TokenType::Identifier("i".to_owned()),
-                                line: 1,
-                                col: 29,
-                            },
+                    Expr::binary(
+                        Expr::variable(Token {
+                            token_type: TokenType::Identifier("i".to_owned()),
+                            line: 1,
+                            col: 29,
                         }),
-                        operator: Operator {
+                        Operator {
                             operator_type: OperatorType::Plus,
                             src_line: 1,
                             src_col: 31,
                         },
-                        right: Box::new(Expr::Literal {
-                            value: Value::Number(1.0),
-                        }),
-                    }),
-                }),
+                        Expr::literal(Value::Number(1.0)),
+                    ),
+                )),
             ])),
         },
     ]);
@@ -711,60 +1011,48 @@ fn can_parse_for_loop_with_single_statement_body() {
                 line: 1,
                 col: 10,
             },
-            initializer: Some(Expr::Literal {
-                value: Value::Number(0.0),
-            }),
+            initializer: Some(Expr::literal(Value::Number(0.0))),
         },
         Stmt::While {
-            condition: Expr::Binary {
-                left: Box::new(Expr::Variable {
-                    name: Token {
-                        token_type: TokenType::Identifier("i".to_owned()),
-                        line: 1,
-                        col: 17,
-                    },
+            condition: Expr::binary(
+                Expr::variable(Token {
+                    token_type: TokenType::Identifier("i".to_owned()),
+                    line: 1,
+                    col: 17,
                 }),
-                operator: Operator {
+                Operator {
                     operator_type: OperatorType::Less,
                     src_line: 1,
                     src_col: 19,
                 },
-                right: Box::new(Expr::Literal {
-                    value: Value::Number(10.0),
-                }),
-            },
+                Expr::literal(Value::Number(10.0)),
+            ),
             body: Box::new(Stmt::Block(vec![
-                Stmt::Print(Expr::Variable {
-                    name: Token {
-                        token_type: TokenType::Identifier("i".to_owned()),
-                        line: 1,
-                        col: 42,
-                    },
-                }),
-                Stmt::Expression(Expr::Assign {
-                    name: Token {
+                Stmt::Print(Expr::variable(Token {
+                    token_type: TokenType::Identifier("i".to_owned()),
+                    line: 1,
+                    col: 42,
+                })),
+                Stmt::Expression(Expr::assign(
+                    Token {
                         token_type: TokenType::Identifier("i".to_owned()),
                         line: 1,
                         col: 25,
                     },
-                    value: Box::new(Expr::Binary {
-                        left: Box::new(Expr::Variable {
-                            name: Token {
-                                token_type: TokenType::Identifier("i".to_owned()),
-                                line: 1,
-                                col: 29,
-                            },
+                    Expr::binary(
+                        Expr::variable(Token {
+                            token_type: TokenType::Identifier("i".to_owned()),
+                            line: 1,
+                            col: 29,
                         }),
-                        operator: Operator {
+                        Operator {
                             operator_type: OperatorType::Plus,
                             src_line: 1,
                             src_col: 31,
                         },
-                        right: Box::new(Expr::Literal {
-                            value: Value::Number(1.0),
-                        }),
-                    }),
-                }),
+                        Expr::literal(Value::Number(1.0)),
+                    ),
+                )),
             ])),
         },
     ]);
@@ -783,18 +1071,506 @@ fn can_parse_for_loop_with_empty_clauses() {
     // There should be no extra block around the while loop if there is no
     // initializer in the for loop.
     let expected = Stmt::While {
-        condition: Expr::Literal {
-            value: Value::Boolean(true),
-        },
-        body: Box::new(Stmt::Print(Expr::Variable {
-            name: Token {
-                token_type: TokenType::Identifier("i".to_owned()),
-                line: 1,
-                col: 16,
-            },
-        })),
+        condition: Expr::literal(Value::Boolean(true)),
+        body: Box::new(Stmt::Print(Expr::variable(Token {
+            token_type: TokenType::Identifier("i".to_owned()),
+            line: 1,
+            col: 16,
+        }))),
     };
 
     assert_eq!(stmts.len(), 1);
     assert_eq!(stmts[0], expected);
 }
+
+#[test]
+fn can_parse_labeled_loop_with_break() {
+    let source = "outer: while (true) break outer;";
+    let mut parser = Parser::new(tokenize(source));
+
+    let stmts = parser.parse().unwrap();
+    let expected = Stmt::Labeled {
+        label: "outer".to_owned(),
+        body: Box::new(Stmt::While {
+            condition: Expr::literal(Value::Boolean(true)),
+            body: Box::new(Stmt::Break {
+                keyword: Token {
+                    token_type: TokenType::Break,
+                    line: 1,
+                    col: 25,
+                },
+                label: Some("outer".to_owned()),
+            }),
+        }),
+    };
+
+    assert_eq!(stmts, [expected]);
+}
+
+#[test]
+fn can_parse_labeled_block() {
+    let result = Parser::new(tokenize("setup: { var a = 1; }"))
+        .parse()
+        .unwrap();
+
+    let [Stmt::Labeled { label, body }] = result.as_slice() else {
+        panic!("expected a single labeled statement");
+    };
+
+    assert_eq!(label, "setup");
+    assert!(matches!(body.as_ref(), Stmt::Block(stmts) if stmts.len() == 1));
+}
+
+#[test]
+fn error_on_break_to_block_label() {
+    let result = Parser::new(tokenize("setup: { break setup; }")).parse();
+
+    assert!(matches!(
+        result,
+        Err(ParserError::UndefinedLabel { ref label, .. }) if label == "setup"
+    ));
+}
+
+#[test]
+fn error_on_break_outside_loop() {
+    let result = Parser::new(tokenize("break;")).parse();
+    let expected = Err(ParserError::BreakOutsideLoop(Token {
+        token_type: TokenType::Break,
+        line: 1,
+        col: 5,
+    }));
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn error_on_break_to_unknown_label() {
+    let result = Parser::new(tokenize("outer: while (true) break inner;")).parse();
+    let expected = Err(ParserError::UndefinedLabel {
+        label: "inner".to_owned(),
+        token: Token {
+            token_type: TokenType::Identifier("inner".to_owned()),
+            line: 1,
+            col: 31,
+        },
+    });
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn error_on_label_without_loop() {
+    let result = Parser::new(tokenize("outer: print 1;")).parse();
+    let expected = Err(ParserError::UnexpectedLanguageComponent {
+        expected: "a loop or block after label".to_owned(),
+        got: "Print".to_owned(),
+    });
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn can_parse_single_expression_from_source() {
+    let expression = Parser::parse_expression("1 + 2 * 3").unwrap();
+
+    assert_eq!(expression.to_string(), "(+ 1 (* 2 3))");
+}
+
+#[test]
+fn error_on_trailing_tokens_after_single_expression() {
+    let result = Parser::parse_expression("1 + 2;");
+    let expected = Err(RloxError::Parser(ParserError::UnexpectedTrailingToken(
+        Token {
+            token_type: TokenType::Semicolon,
+            line: 1,
+            col: 6,
+        },
+    )));
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn operator_precedence_and_associativity() {
+    let cases = [
+        ("1 + 2 * 3", "(+ 1 (* 2 3))"),
+        ("1 * 2 + 3", "(+ (* 1 2) 3)"),
+        ("1 - 2 - 3", "(- (- 1 2) 3)"),
+        ("1 / 2 / 3", "(/ (/ 1 2) 3)"),
+        ("1 + 7 % 3", "(+ 1 (% 7 3))"),
+        ("7 % 3 * 2", "(* (% 7 3) 2)"),
+        ("-2 * 3", "(* (- 2) 3)"),
+        ("--2", "(- (- 2))"),
+        ("+5", "(+ 5)"),
+        ("1 - +2", "(- 1 (+ 2))"),
+        ("!!true", "(! (! true))"),
+        ("!true == false", "(== (! true) false)"),
+        ("1 < 2 == 3 > 4", "(== (< 1 2) (> 3 4))"),
+        ("1 + 2 < 3 * 4", "(< (+ 1 2) (* 3 4))"),
+        ("nil ?? 1 == 2", "(?? nil (== 1 2))"),
+        ("nil ?? nil ?? 1", "(?? (?? nil nil) 1)"),
+        ("true or false and false", "(or true (and false false))"),
+        ("1 == 1 and 2 < 3", "(and (== 1 1) (< 2 3))"),
+        ("nil ?? false or true", "(?? nil (or false true))"),
+        (r#"-"f"(1)"#, r#"(- (call "f" 1))"#),
+        ("(1 + 2) * 3", "(* (group (+ 1 2)) 3)"),
+    ];
+
+    for (source, expected) in cases {
+        let expression = Parser::parse_expression(source).unwrap();
+
+        assert_eq!(expression.to_string(), expected, "source: {source}");
+    }
+}
+
+#[test]
+fn logical_operators_convert_and_display_as_keywords() {
+    for (token_type, operator_type, expected) in [
+        (TokenType::And, OperatorType::And, "and"),
+        (TokenType::Or, OperatorType::Or, "or"),
+    ] {
+        let operator = Operator::try_from(Token {
+            token_type,
+            line: 1,
+            col: 5,
+        })
+        .unwrap();
+
+        assert_eq!(operator.operator_type, operator_type);
+        assert_eq!(operator.to_string(), expected);
+    }
+
+    let cases = [
+        ("true and false", "(and true false)"),
+        ("nil or 1", "(or nil 1)"),
+    ];
+
+    for (source, expected) in cases {
+        let expression = Parser::parse_expression(source).unwrap();
+
+        assert_eq!(expression.to_string(), expected, "source: {source}");
+    }
+}
+
+/// Like [tokenize], but ends the tokens with an `Eof` token like lexers that emit one do.
+fn tokenize_with_eof(src: &str) -> Vec<Token> {
+    let mut tokens = tokenize(src);
+    tokens.push(Token {
+        token_type: TokenType::Eof,
+        line: 1,
+        col: src.len(),
+    });
+
+    tokens
+}
+
+#[test]
+fn can_parse_tokens_ending_with_eof() {
+    let sources = [
+        "print 1 + 2;",
+        "var a = 1; { a = 2; }",
+        "while (false) break;",
+    ];
+
+    for source in sources {
+        let with_eof = Parser::new(tokenize_with_eof(source)).parse();
+        let without_eof = Parser::new(tokenize(source)).parse();
+
+        assert_eq!(with_eof, without_eof, "source: {source}");
+    }
+}
+
+#[test]
+fn error_on_incomplete_tokens_ending_with_eof() {
+    let result = Parser::new(tokenize_with_eof("1 +")).parse();
+
+    assert_eq!(result, Err(ParserError::UnexpectedEndOfTokens));
+}
+
+#[test]
+fn lenient_parser_accepts_final_expression_before_eof() {
+    let mut parser = Parser::new(tokenize_with_eof("1 + 2"));
+    parser.set_lenient(true);
+
+    let statements = parser.parse().unwrap();
+
+    assert_eq!(statements.len(), 1);
+}
+
+#[test]
+fn error_on_deeply_nested_parentheses() {
+    let source = format!("{}1{};", "(".repeat(10_000), ")".repeat(10_000));
+
+    let result = Parser::new(tokenize(&source)).parse();
+
+    assert_eq!(
+        result,
+        Err(ParserError::NestingTooDeep { line: 1, col: 65 })
+    );
+}
+
+#[test]
+fn error_on_deeply_nested_unary_operators() {
+    let source = format!("{}1;", "-".repeat(10_000));
+
+    let result = Parser::new(tokenize(&source)).parse();
+
+    assert_eq!(
+        result,
+        Err(ParserError::NestingTooDeep { line: 1, col: 65 })
+    );
+}
+
+#[test]
+fn error_on_deeply_nested_blocks() {
+    let source = format!("{}{}", "{".repeat(200_000), "}".repeat(200_000));
+
+    let result = Parser::new(tokenize(&source)).parse();
+
+    assert_eq!(
+        result,
+        Err(ParserError::NestingTooDeep { line: 1, col: 65 })
+    );
+}
+
+#[test]
+fn error_on_deeply_chained_assignments() {
+    let source = format!("{}1;", "a = ".repeat(200_000));
+
+    let result = Parser::new(tokenize(&source)).parse();
+
+    assert_eq!(
+        result,
+        Err(ParserError::NestingTooDeep { line: 1, col: 257 })
+    );
+}
+
+#[test]
+fn can_parse_nesting_up_to_the_limit() {
+    let source = format!("{}1{};", "(".repeat(5), ")".repeat(5));
+
+    let mut parser = Parser::new(tokenize(&source));
+    parser.set_max_nesting_depth(6);
+    assert!(parser.parse().is_ok());
+
+    let mut parser = Parser::new(tokenize(&source));
+    parser.set_max_nesting_depth(5);
+    assert_eq!(
+        parser.parse(),
+        Err(ParserError::NestingTooDeep { line: 1, col: 6 })
+    );
+}
+
+#[test]
+fn can_parse_empty_input() {
+    assert_eq!(Parser::new([]).parse(), Ok(Vec::new()));
+    assert_eq!(Parser::new(tokenize_with_eof("")).parse(), Ok(Vec::new()));
+    assert_eq!(Parser::new([]).parse_all(), (Vec::new(), Vec::new()));
+}
+
+#[test]
+fn can_parse_one_declaration_at_a_time() {
+    let mut parser = Parser::new(tokenize_with_eof("var a = 1; print a;"));
+
+    let first = parser.parse_one().unwrap().unwrap();
+    assert!(matches!(first, Stmt::Var { .. }), "{first:?}");
+
+    let second = parser.parse_one().unwrap().unwrap();
+    assert!(matches!(second, Stmt::Print(_)), "{second:?}");
+
+    assert_eq!(parser.parse_one(), Ok(None));
+    assert_eq!(parser.parse_one(), Ok(None));
+}
+
+#[test]
+fn parse_one_recovers_after_an_error() {
+    let mut parser = Parser::new(tokenize("var = 1; print 2;"));
+
+    assert!(parser.parse_one().is_err());
+
+    let statement = parser.parse_one().unwrap().unwrap();
+    assert!(matches!(statement, Stmt::Print(_)), "{statement:?}");
+    assert_eq!(parser.parse_one(), Ok(None));
+}
+
+#[test]
+fn parse_output_matches_pinned_corpus() {
+    // Displayed and JSON forms of each program, pinned so that changes to how trees are stored
+    // cannot change what they parse into
+    let corpus = [
+        (
+            "1 + 2 * 3 - -4 / (5 % 6);",
+            "(- (+ 1 (* 2 3)) (/ (- 4) (group (% 5 6))));",
+            concat!(
+                r#"[{"kind":"Expression","expression":{"kind":"Binary","operator":"-","#,
+                r#""left":{"kind":"Binary","operator":"+","left":{"kind":"Literal","value":1},"#,
+                r#""right":{"kind":"Binary","operator":"*","left":{"kind":"Literal","value":2},"#,
+                r#""right":{"kind":"Literal","value":3}}},"right":{"kind":"Binary","operator":"/","#,
+                r#""left":{"kind":"Unary","operator":"-","right":{"kind":"Literal","value":4}},"#,
+                r#""right":{"kind":"Grouping","expression":{"kind":"Binary","operator":"%","#,
+                r#""left":{"kind":"Literal","value":5},"right":{"kind":"Literal","value":6}}}}}}]"#,
+            ),
+        ),
+        (
+            "a = b = c ?? d or e and !f;",
+            "(assign Identifier a <- (assign Identifier b <- (?? (var Identifier c) (or (var Identifier d) (and (var Identifier e) (! (var Identifier f)))))));",
+            concat!(
+                r#"[{"kind":"Expression","expression":{"kind":"Assign","name":"a","#,
+                r#""value":{"kind":"Assign","name":"b","value":{"kind":"Logical","operator":"??","#,
+                r#""left":{"kind":"Variable","name":"c"},"right":{"kind":"Logical","operator":"or","#,
+                r#""left":{"kind":"Variable","name":"d"},"right":{"kind":"Logical","#,
+                r#""operator":"and","left":{"kind":"Variable","name":"e"},"right":{"kind":"Unary","#,
+                r#""operator":"!","right":{"kind":"Variable","name":"f"}}}}}}}}]"#,
+            ),
+        ),
+        (
+            r#"print concat(1, "x", true)[0];"#,
+            r#"(print (index (call (var Identifier concat) 1 "x" true) 0));"#,
+            concat!(
+                r#"[{"kind":"Print","expression":{"kind":"Index","object":{"kind":"Call","#,
+                r#""callee":{"kind":"Variable","name":"concat"},"arguments":[{"kind":"Literal","#,
+                r#""value":1},{"kind":"Literal","value":"x"},{"kind":"Literal","value":true}]},"#,
+                r#""index":{"kind":"Literal","value":0}}}]"#,
+            ),
+        ),
+        (
+            "f(1)(2,)[3][a + 1];",
+            "(index (index (call (call (var Identifier f) 1) 2) 3) (+ (var Identifier a) 1));",
+            concat!(
+                r#"[{"kind":"Expression","expression":{"kind":"Index","object":{"kind":"Index","#,
+                r#""object":{"kind":"Call","callee":{"kind":"Call","callee":{"kind":"Variable","#,
+                r#""name":"f"},"arguments":[{"kind":"Literal","value":1}]},"#,
+                r#""arguments":[{"kind":"Literal","value":2}]},"index":{"kind":"Literal","#,
+                r#""value":3}},"index":{"kind":"Binary","operator":"+","left":{"kind":"Variable","#,
+                r#""name":"a"},"right":{"kind":"Literal","value":1}}}}]"#,
+            ),
+        ),
+        (
+            "x == y != z < 1 <= 2 > 3 >= 4;",
+            "(!= (== (var Identifier x) (var Identifier y)) (>= (> (<= (< (var Identifier z) 1) 2) 3) 4));",
+            concat!(
+                r#"[{"kind":"Expression","expression":{"kind":"Binary","operator":"!=","#,
+                r#""left":{"kind":"Binary","operator":"==","left":{"kind":"Variable","name":"x"},"#,
+                r#""right":{"kind":"Variable","name":"y"}},"right":{"kind":"Binary","#,
+                r#""operator":">=","left":{"kind":"Binary","operator":">","left":{"kind":"Binary","#,
+                r#""operator":"<=","left":{"kind":"Binary","operator":"<","#,
+                r#""left":{"kind":"Variable","name":"z"},"right":{"kind":"Literal","value":1}},"#,
+                r#""right":{"kind":"Literal","value":2}},"right":{"kind":"Literal","value":3}},"#,
+                r#""right":{"kind":"Literal","value":4}}}}]"#,
+            ),
+        ),
+        (
+            r#"var s = "h\u{e9}llo"[+1];"#,
+            r#"(var Identifier s = (index "héllo" (+ 1)));"#,
+            concat!(
+                r#"[{"kind":"Var","name":"s","initializer":{"kind":"Index","#,
+                r#""object":{"kind":"Literal","value":"héllo"},"index":{"kind":"Unary","#,
+                r#""operator":"+","right":{"kind":"Literal","value":1}}}}]"#,
+            ),
+        ),
+        (
+            "for (var i = 0; i < 3; i = i + 1) print i;",
+            "{ (var Identifier i = 0); (While (< (var Identifier i) 3) is true => { (print (var Identifier i)); (assign Identifier i <- (+ (var Identifier i) 1)); }) }",
+            concat!(
+                r#"[{"kind":"Block","statements":[{"kind":"Var","name":"i","#,
+                r#""initializer":{"kind":"Literal","value":0}},{"kind":"While","#,
+                r#""condition":{"kind":"Binary","operator":"<","left":{"kind":"Variable","#,
+                r#""name":"i"},"right":{"kind":"Literal","value":3}},"body":{"kind":"Block","#,
+                r#""statements":[{"kind":"Print","expression":{"kind":"Variable","name":"i"}},"#,
+                r#"{"kind":"Expression","expression":{"kind":"Assign","name":"i","#,
+                r#""value":{"kind":"Binary","operator":"+","left":{"kind":"Variable","name":"i"},"#,
+                r#""right":{"kind":"Literal","value":1}}}}]}}]}]"#,
+            ),
+        ),
+        (
+            "if (nil) print 1; else { print (((2))); }",
+            "(If nil then (print 1); else { (print (group (group (group 2)))); })",
+            concat!(
+                r#"[{"kind":"If","condition":{"kind":"Literal","value":null},"#,
+                r#""then_branch":{"kind":"Print","expression":{"kind":"Literal","value":1}},"#,
+                r#""else_branch":{"kind":"Block","statements":[{"kind":"Print","#,
+                r#""expression":{"kind":"Grouping","expression":{"kind":"Grouping","#,
+                r#""expression":{"kind":"Grouping","expression":{"kind":"Literal","#,
+                r#""value":2}}}}}]}}]"#,
+            ),
+        ),
+        (
+            "outer: while (false) { a = -(b); break outer; }",
+            "(outer: (While false is true => { (assign Identifier a <- (- (group (var Identifier b)))); (break outer); }))",
+            concat!(
+                r#"[{"kind":"Labeled","label":"outer","body":{"kind":"While","#,
+                r#""condition":{"kind":"Literal","value":false},"body":{"kind":"Block","#,
+                r#""statements":[{"kind":"Expression","expression":{"kind":"Assign","name":"a","#,
+                r#""value":{"kind":"Unary","operator":"-","right":{"kind":"Grouping","#,
+                r#""expression":{"kind":"Variable","name":"b"}}}}},{"kind":"Break","label":"outer","#,
+                r#""line":1,"col":38}]}}}]"#,
+            ),
+        ),
+    ];
+
+    for (source, displayed, json) in corpus {
+        let statements = crate::parse(source).unwrap();
+
+        let statements_displayed = statements
+            .iter()
+            .map(Stmt::to_string)
+            .collect::<Vec<_>>()
+            .join(" ");
+        assert_eq!(statements_displayed, displayed, "displaying {source}");
+        assert_eq!(
+            super::json::to_json(&statements),
+            json,
+            "converting {source}"
+        );
+    }
+}
+
+#[test]
+fn expressions_compare_by_structure_rather_than_arena_layout() {
+    let plus = Operator {
+        operator_type: OperatorType::Plus,
+        src_line: 1,
+        src_col: 3,
+    };
+    let one = ExprNode::Literal {
+        value: Value::Number(1.0),
+    };
+    let two = ExprNode::Literal {
+        value: Value::Number(2.0),
+    };
+
+    // Add the right operand before the left one, unlike the parser
+    let mut builder = ExprBuilder::new();
+    let right = builder.push(two);
+    let left = builder.push(one);
+    let root = builder.push(ExprNode::Binary {
+        left,
+        operator: plus.clone(),
+        right,
+    });
+    let reordered = builder.finish(root);
+
+    let one = || Expr::literal(Value::Number(1.0));
+    let two = || Expr::literal(Value::Number(2.0));
+
+    assert_eq!(reordered, Expr::binary(one(), plus.clone(), two()));
+    assert_ne!(reordered, Expr::binary(two(), plus, one()));
+}
+
+#[test]
+fn expression_trees_hold_only_their_own_nodes() {
+    // The assignment target turns into the assignment instead of being left behind
+    let assignment = Parser::parse_expression("a = b = 1").unwrap();
+    assert_eq!(assignment.node_count(), 3);
+
+    // Nodes of an expression that failed to parse do not end up in the next one
+    let tokens = tokenize("print (1 + 2; print 2 * 3;");
+    let (statements, errors) = Parser::new(tokens).parse_all();
+    assert_eq!(errors.len(), 1);
+
+    let [Stmt::Print(expr)] = statements.as_slice() else {
+        panic!("expected a single print statement");
+    };
+    assert_eq!(expr.to_string(), "(* 2 3)");
+    assert_eq!(expr.node_count(), 3);
+}
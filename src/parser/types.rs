@@ -1,18 +1,34 @@
-use std::fmt::Display;
+use std::{fmt::Display, rc::Rc};
 
 use crate::lexer::token::{Token, TokenType};
 
 use super::error::ParserError;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Stmt {
     Block(Vec<Stmt>),
+    Break {
+        keyword: Token,
+        /// Label of the loop to break out of, or `None` for the innermost loop
+        label: Option<String>,
+    },
+    /// A variable that cannot be reassigned after its declaration
+    Const {
+        name: Token,
+        initializer: Expr,
+    },
     Expression(Expr),
     If {
         condition: Expr,
         then_branch: Box<Stmt>,
         else_branch: Option<Box<Stmt>>,
     },
+    /// A loop with a label that `break` statements can refer to, or a block with a label naming
+    /// its scope
+    Labeled {
+        label: String,
+        body: Box<Stmt>,
+    },
     Print(Expr),
     Var {
         name: Token,
@@ -28,12 +44,21 @@ impl Stmt {
     pub fn name(&self) -> &'static str {
         match self {
             Stmt::Block(_) => "block",
+            Stmt::Break {
+                keyword: _,
+                label: _,
+            } => "break statement",
+            Stmt::Const {
+                name: _,
+                initializer: _,
+            } => "constant declaration",
             Stmt::Expression(_) => "expression statement",
             Stmt::If {
                 condition: _,
                 then_branch: _,
                 else_branch: _,
             } => "if statement",
+            Stmt::Labeled { label: _, body: _ } => "labeled statement",
             Stmt::Print(_) => "print statement",
             Stmt::Var {
                 name: _,
@@ -47,40 +72,413 @@ impl Stmt {
     }
 }
 
+/// An expression tree. Its nodes are stored together in one arena and refer to their
+/// sub-expressions by [ExprId], so a tree costs a single allocation instead of one per node.
+///
+/// Two trees are equal if they have the same structure, regardless of where in their arenas the
+/// nodes are stored.
+#[derive(Debug, Clone)]
+pub struct Expr {
+    nodes: Vec<ExprNode>,
+    root: ExprId,
+}
+
+/// Identifies a node in the arena of the [Expr] or [ExprBuilder] that created it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExprId(usize);
+
 #[derive(Debug, Clone, PartialEq)]
-pub enum Expr {
-    // TODO: Do these later.
+pub enum ExprNode {
     Assign {
         name: Token,
-        value: Box<Expr>,
+        value: ExprId,
     },
     Binary {
-        left: Box<Expr>,
+        left: ExprId,
         operator: Operator,
-        right: Box<Expr>,
+        right: ExprId,
+    },
+    Call {
+        callee: ExprId,
+        /// The closing parenthesis of the argument list
+        paren: Token,
+        arguments: Vec<ExprId>,
     },
     Grouping {
-        inner: Box<Expr>,
+        inner: ExprId,
+    },
+    /// Indexing into a string, like `s[0]`. Indices count characters rather than bytes.
+    Index {
+        object: ExprId,
+        /// The closing bracket of the index
+        bracket: Token,
+        index: ExprId,
     },
     Literal {
         value: Value,
     },
+    /// A short-circuiting binary expression
+    Logical {
+        left: ExprId,
+        operator: Operator,
+        right: ExprId,
+    },
     Unary {
         operator: Operator,
-        right: Box<Expr>,
+        right: ExprId,
     },
     Variable {
         name: Token,
     },
 }
 
+/// A node of an [Expr] together with the arena its sub-expressions are looked up in.
+#[derive(Debug, Clone, Copy)]
+pub struct ExprRef<'a> {
+    expr: &'a Expr,
+    id: ExprId,
+}
+
+/// Collects the nodes of an [Expr] as it is built bottom up, sub-expressions first.
+#[derive(Debug, Default)]
+pub struct ExprBuilder {
+    nodes: Vec<ExprNode>,
+}
+
+impl Expr {
+    /// The outermost node of the tree.
+    pub fn root(&self) -> ExprRef<'_> {
+        self.get(self.root)
+    }
+
+    /// The node `id` of this tree.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` was not created for this tree.
+    pub fn get(&self, id: ExprId) -> ExprRef<'_> {
+        assert!(id.0 < self.nodes.len(), "{id:?} is not part of this tree");
+
+        ExprRef { expr: self, id }
+    }
+
+    /// Number of nodes in the tree.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn assign(name: Token, value: Expr) -> Self {
+        let mut builder = ExprBuilder::new();
+        let value = builder.append(value);
+        builder.finish_with(ExprNode::Assign { name, value })
+    }
+
+    pub fn binary(left: Expr, operator: Operator, right: Expr) -> Self {
+        let mut builder = ExprBuilder::new();
+        let left = builder.append(left);
+        let right = builder.append(right);
+        builder.finish_with(ExprNode::Binary {
+            left,
+            operator,
+            right,
+        })
+    }
+
+    pub fn call(callee: Expr, paren: Token, arguments: Vec<Expr>) -> Self {
+        let mut builder = ExprBuilder::new();
+        let callee = builder.append(callee);
+        let arguments = arguments
+            .into_iter()
+            .map(|argument| builder.append(argument))
+            .collect();
+        builder.finish_with(ExprNode::Call {
+            callee,
+            paren,
+            arguments,
+        })
+    }
+
+    pub fn grouping(inner: Expr) -> Self {
+        let mut builder = ExprBuilder::new();
+        let inner = builder.append(inner);
+        builder.finish_with(ExprNode::Grouping { inner })
+    }
+
+    pub fn index(object: Expr, bracket: Token, index: Expr) -> Self {
+        let mut builder = ExprBuilder::new();
+        let object = builder.append(object);
+        let index = builder.append(index);
+        builder.finish_with(ExprNode::Index {
+            object,
+            bracket,
+            index,
+        })
+    }
+
+    pub fn literal(value: Value) -> Self {
+        ExprBuilder::new().finish_with(ExprNode::Literal { value })
+    }
+
+    pub fn logical(left: Expr, operator: Operator, right: Expr) -> Self {
+        let mut builder = ExprBuilder::new();
+        let left = builder.append(left);
+        let right = builder.append(right);
+        builder.finish_with(ExprNode::Logical {
+            left,
+            operator,
+            right,
+        })
+    }
+
+    pub fn unary(operator: Operator, right: Expr) -> Self {
+        let mut builder = ExprBuilder::new();
+        let right = builder.append(right);
+        builder.finish_with(ExprNode::Unary { operator, right })
+    }
+
+    pub fn variable(name: Token) -> Self {
+        ExprBuilder::new().finish_with(ExprNode::Variable { name })
+    }
+}
+
+impl PartialEq for Expr {
+    fn eq(&self, other: &Self) -> bool {
+        self.root() == other.root()
+    }
+}
+
+impl<'a> ExprRef<'a> {
+    pub fn id(&self) -> ExprId {
+        self.id
+    }
+
+    pub fn node(&self) -> &'a ExprNode {
+        &self.expr.nodes[self.id.0]
+    }
+
+    /// The sub-expression `id` of this node, looked up in the same tree.
+    pub fn child(&self, id: ExprId) -> ExprRef<'a> {
+        self.expr.get(id)
+    }
+}
+
+impl PartialEq for ExprRef<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        use ExprNode::*;
+
+        let children_eq = |lhs: &ExprId, rhs: &ExprId| self.child(*lhs) == other.child(*rhs);
+
+        match (self.node(), other.node()) {
+            (
+                Assign { name, value },
+                Assign {
+                    name: other_name,
+                    value: other_value,
+                },
+            ) => name == other_name && children_eq(value, other_value),
+            (
+                Binary {
+                    left,
+                    operator,
+                    right,
+                },
+                Binary {
+                    left: other_left,
+                    operator: other_operator,
+                    right: other_right,
+                },
+            )
+            | (
+                Logical {
+                    left,
+                    operator,
+                    right,
+                },
+                Logical {
+                    left: other_left,
+                    operator: other_operator,
+                    right: other_right,
+                },
+            ) => {
+                operator == other_operator
+                    && children_eq(left, other_left)
+                    && children_eq(right, other_right)
+            }
+            (
+                Call {
+                    callee,
+                    paren,
+                    arguments,
+                },
+                Call {
+                    callee: other_callee,
+                    paren: other_paren,
+                    arguments: other_arguments,
+                },
+            ) => {
+                paren == other_paren
+                    && children_eq(callee, other_callee)
+                    && arguments.len() == other_arguments.len()
+                    && arguments
+                        .iter()
+                        .zip(other_arguments)
+                        .all(|(lhs, rhs)| children_eq(lhs, rhs))
+            }
+            (Grouping { inner }, Grouping { inner: other_inner }) => {
+                children_eq(inner, other_inner)
+            }
+            (
+                Index {
+                    object,
+                    bracket,
+                    index,
+                },
+                Index {
+                    object: other_object,
+                    bracket: other_bracket,
+                    index: other_index,
+                },
+            ) => {
+                bracket == other_bracket
+                    && children_eq(object, other_object)
+                    && children_eq(index, other_index)
+            }
+            (Literal { value }, Literal { value: other_value }) => value == other_value,
+            (
+                Unary { operator, right },
+                Unary {
+                    operator: other_operator,
+                    right: other_right,
+                },
+            ) => operator == other_operator && children_eq(right, other_right),
+            (Variable { name }, Variable { name: other_name }) => name == other_name,
+            _ => false,
+        }
+    }
+}
+
+impl ExprBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `node` to the arena. Its sub-expressions must already have been added.
+    pub fn push(&mut self, node: ExprNode) -> ExprId {
+        self.nodes.push(node);
+        ExprId(self.nodes.len() - 1)
+    }
+
+    /// The node `id` added to this builder.
+    pub fn get(&self, id: ExprId) -> &ExprNode {
+        &self.nodes[id.0]
+    }
+
+    /// Replaces the node `id` with `node`, returning the node it replaced.
+    pub fn replace(&mut self, id: ExprId, node: ExprNode) -> ExprNode {
+        std::mem::replace(&mut self.nodes[id.0], node)
+    }
+
+    /// Moves every node of `expr` into this builder, returning the new id of its root.
+    pub fn append(&mut self, expr: Expr) -> ExprId {
+        let offset = self.nodes.len();
+        let shift = |id: ExprId| ExprId(id.0 + offset);
+
+        self.nodes
+            .extend(expr.nodes.into_iter().map(|node| match node {
+                ExprNode::Assign { name, value } => ExprNode::Assign {
+                    name,
+                    value: shift(value),
+                },
+                ExprNode::Binary {
+                    left,
+                    operator,
+                    right,
+                } => ExprNode::Binary {
+                    left: shift(left),
+                    operator,
+                    right: shift(right),
+                },
+                ExprNode::Call {
+                    callee,
+                    paren,
+                    arguments,
+                } => ExprNode::Call {
+                    callee: shift(callee),
+                    paren,
+                    arguments: arguments.into_iter().map(shift).collect(),
+                },
+                ExprNode::Grouping { inner } => ExprNode::Grouping {
+                    inner: shift(inner),
+                },
+                ExprNode::Index {
+                    object,
+                    bracket,
+                    index,
+                } => ExprNode::Index {
+                    object: shift(object),
+                    bracket,
+                    index: shift(index),
+                },
+                ExprNode::Logical {
+                    left,
+                    operator,
+                    right,
+                } => ExprNode::Logical {
+                    left: shift(left),
+                    operator,
+                    right: shift(right),
+                },
+                ExprNode::Unary { operator, right } => ExprNode::Unary {
+                    operator,
+                    right: shift(right),
+                },
+                node @ (ExprNode::Literal { .. } | ExprNode::Variable { .. }) => node,
+            }));
+
+        shift(expr.root)
+    }
+
+    /// Turns the nodes added so far into a tree with `root` as its outermost node, leaving the
+    /// builder empty.
+    pub fn finish(&mut self, root: ExprId) -> Expr {
+        Expr {
+            nodes: std::mem::take(&mut self.nodes),
+            root,
+        }
+    }
+
+    /// Adds `node` and finishes the tree with it as the root.
+    fn finish_with(mut self, node: ExprNode) -> Expr {
+        let root = self.push(node);
+        self.finish(root)
+    }
+}
+
 /// Types of valid values in the Lox language
 #[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     Boolean(bool),
+    NativeFunction(NativeFunction),
     Nil,
     Number(f64),
-    String(String),
+    /// Strings are immutable, so clones share the same storage
+    String(Rc<str>),
+}
+
+/// A function implemented in Rust that is callable from the Lox language
+#[derive(Debug, Clone)]
+pub struct NativeFunction {
+    pub name: &'static str,
+    /// Number of arguments the function expects, or `None` if it accepts any number of arguments
+    pub arity: Option<usize>,
+    /// Returns an error message when called with invalid arguments
+    pub function: fn(&[Value]) -> Result<Value, String>,
+}
+
+impl PartialEq for NativeFunction {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
 }
 
 impl Value {
@@ -88,13 +486,68 @@ impl Value {
         matches!(self, Value::Number(_))
     }
 
+    /// Returns the name of the value's type as it is referred to in Lox
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Boolean(_) => "boolean",
+            Value::NativeFunction(_) => "native function",
+            Value::Nil => "nil",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+        }
+    }
+
+    /// Returns true if the value can be called like a function
+    pub fn is_callable(&self) -> bool {
+        matches!(self, Value::NativeFunction(_))
+    }
+
+    /// Returns the number of arguments the value expects when called. Returns `None` if the
+    /// value is not callable or accepts any number of arguments.
+    pub fn arity(&self) -> Option<usize> {
+        match self {
+            Value::NativeFunction(native) => native.arity,
+            _ => None,
+        }
+    }
+
+    /// Returns the number if the value is a number
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Value::Number(number) => Some(*number),
+            _ => None,
+        }
+    }
+
+    /// Returns the boolean if the value is a boolean
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Boolean(boolean) => Some(*boolean),
+            _ => None,
+        }
+    }
+
+    /// Returns the string if the value is a string
+    pub fn as_string(&self) -> Option<&str> {
+        match self {
+            Value::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
     pub fn all_is_number<'a, I: IntoIterator<Item = &'a Value>>(values: I) -> bool {
         values.into_iter().all(Value::is_number)
     }
 
+    /// Converts the value to a boolean, the same as [Value::is_truthy]
+    pub fn to_bool(&self) -> bool {
+        self.is_truthy()
+    }
+
     pub fn is_truthy(&self) -> bool {
         match self {
             Value::Boolean(boolean) => *boolean,
+            Value::NativeFunction(_) => true,
             Value::Nil => false,
             Value::Number(num) => *num != 0.0,
             Value::String(_) => true,
@@ -106,13 +559,64 @@ impl Value {
     /// Convert `Value` to its intended printing format when printed as a value in the Lox
     /// programming language
     pub fn stringify(&self) -> String {
-        match self {
-            Value::String(string) => string.clone(),
+        self.stringify_with(NumberFormat::Default)
+    }
+
+    /// Like [Value::stringify], but formats numbers according to `number_format`
+    pub fn stringify_with(&self, number_format: NumberFormat) -> String {
+        match (self, number_format) {
+            (Value::String(string), _) => string.to_string(),
+            (Value::Number(number), NumberFormat::Fixed(decimals)) => {
+                format!("{number:.decimals$}")
+            }
+            (Value::Number(number), NumberFormat::Scientific) => format!("{number:e}"),
             _ => self.to_string(),
         }
     }
 }
 
+impl From<f64> for Value {
+    fn from(number: f64) -> Self {
+        Value::Number(number)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(boolean: bool) -> Self {
+        Value::Boolean(boolean)
+    }
+}
+
+impl From<String> for Value {
+    fn from(string: String) -> Self {
+        Value::String(string.into())
+    }
+}
+
+impl From<&str> for Value {
+    fn from(string: &str) -> Self {
+        Value::String(string.into())
+    }
+}
+
+impl From<()> for Value {
+    fn from(_: ()) -> Self {
+        Value::Nil
+    }
+}
+
+/// How numbers are formatted when they are printed
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum NumberFormat {
+    /// Integers without a decimal point and other numbers with as many decimals as needed
+    #[default]
+    Default,
+    /// A fixed number of decimals, like `%.2f`
+    Fixed(usize),
+    /// Scientific notation, like `1.5e3`
+    Scientific,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Operator {
     pub operator_type: OperatorType,
@@ -127,6 +631,7 @@ pub enum OperatorType {
     Dot,
 
     Minus,
+    Percent,
     Plus,
     Slash,
     Star,
@@ -139,6 +644,10 @@ pub enum OperatorType {
     GreaterEqual,
     Less,
     LessEqual,
+
+    And,
+    Or,
+    QuestionQuestion,
 }
 
 impl TryFrom<Token> for Operator {
@@ -148,6 +657,7 @@ impl TryFrom<Token> for Operator {
         let operator_type = match token.token_type {
             TokenType::Dot => OperatorType::Dot,
             TokenType::Minus => OperatorType::Minus,
+            TokenType::Percent => OperatorType::Percent,
             TokenType::Plus => OperatorType::Plus,
             TokenType::Slash => OperatorType::Slash,
             TokenType::Star => OperatorType::Star,
@@ -159,6 +669,9 @@ impl TryFrom<Token> for Operator {
             TokenType::GreaterEqual => OperatorType::GreaterEqual,
             TokenType::Less => OperatorType::Less,
             TokenType::LessEqual => OperatorType::LessEqual,
+            TokenType::And => OperatorType::And,
+            TokenType::Or => OperatorType::Or,
+            TokenType::QuestionQuestion => OperatorType::QuestionQuestion,
             _ => return Err(ParserError::InvalidTokenToOperatorConversion(token)),
         };
 
@@ -172,17 +685,43 @@ impl TryFrom<Token> for Operator {
 
 impl Display for Expr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let formatted_string = match self {
-            Expr::Binary {
+        write!(f, "{}", self.root())
+    }
+}
+
+impl Display for ExprRef<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let child = |id: &ExprId| self.child(*id);
+
+        let formatted_string = match self.node() {
+            ExprNode::Binary {
                 left,
                 operator,
                 right,
-            } => format!("({operator} {left} {right})"),
-            Expr::Grouping { inner } => format!("(group {inner})"),
-            Expr::Literal { value } => format!("{value}"),
-            Expr::Unary { operator, right } => format!("({operator} {right})"),
-            Expr::Assign { name, value } => format!("(assign {name} <- {value})"),
-            Expr::Variable { name } => format!("(var {name})"),
+            } => format!("({operator} {} {})", child(left), child(right)),
+            ExprNode::Call {
+                callee, arguments, ..
+            } => {
+                let arguments = arguments.iter().fold(String::new(), |mut acc, argument| {
+                    acc.push_str(&format!(" {}", child(argument)));
+                    acc
+                });
+
+                format!("(call {}{arguments})", child(callee))
+            }
+            ExprNode::Grouping { inner } => format!("(group {})", child(inner)),
+            ExprNode::Index { object, index, .. } => {
+                format!("(index {} {})", child(object), child(index))
+            }
+            ExprNode::Literal { value } => format!("{value}"),
+            ExprNode::Logical {
+                left,
+                operator,
+                right,
+            } => format!("({operator} {} {})", child(left), child(right)),
+            ExprNode::Unary { operator, right } => format!("({operator} {})", child(right)),
+            ExprNode::Assign { name, value } => format!("(assign {name} <- {})", child(value)),
+            ExprNode::Variable { name } => format!("(var {name})"),
         };
 
         write!(f, "{formatted_string}")
@@ -194,6 +733,7 @@ impl Display for Operator {
         let string = match self.operator_type {
             OperatorType::Dot => ".",
             OperatorType::Minus => "-",
+            OperatorType::Percent => "%",
             OperatorType::Plus => "+",
             OperatorType::Slash => "/",
             OperatorType::Star => "*",
@@ -205,6 +745,9 @@ impl Display for Operator {
             OperatorType::GreaterEqual => ">=",
             OperatorType::Less => "<",
             OperatorType::LessEqual => "<=",
+            OperatorType::And => "and",
+            OperatorType::Or => "or",
+            OperatorType::QuestionQuestion => "??",
         };
 
         write!(f, "{string}")
@@ -215,15 +758,10 @@ impl Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let string = match self {
             Value::Boolean(boolean) => boolean.to_string(),
+            Value::NativeFunction(native) => format!("<native fn {}>", native.name),
             Value::Nil => "nil".to_string(),
-            // Display integer floats without the decimal point
-            Value::Number(number) => {
-                if number.fract() == 0.0 {
-                    (*number as u64).to_string()
-                } else {
-                    number.to_string()
-                }
-            }
+            // Integer floats are already displayed without the decimal point
+            Value::Number(number) => number.to_string(),
             Value::String(string) => format!("\"{string}\""),
         };
 
@@ -234,14 +772,23 @@ impl Display for Value {
 impl Display for Stmt {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let string = match self {
-            Stmt::Block(expressions) => {
-                let string = expressions.iter().fold(String::new(), |mut acc, stmt| {
-                    acc.push_str(&format!("{stmt} "));
-                    acc
-                });
+            Stmt::Block(statements) => {
+                if statements.is_empty() {
+                    "{ }".to_owned()
+                } else {
+                    let string = statements
+                        .iter()
+                        .map(|stmt| stmt.to_string())
+                        .collect::<Vec<String>>()
+                        .join(" ");
 
-                format!("{{ {string} }}")
+                    format!("{{ {string} }}")
+                }
             }
+            Stmt::Break { label, .. } => match label {
+                Some(label) => format!("(break {label});"),
+                None => "(break);".to_owned(),
+            },
             Stmt::Expression(expr) => format!("{expr};"),
             Stmt::If {
                 condition,
@@ -255,7 +802,9 @@ impl Display for Stmt {
                 };
                 format!("(If {condition} then {then_body}{else_body})")
             }
+            Stmt::Labeled { label, body } => format!("({label}: {body})"),
             Stmt::Print(expr) => format!("(print {expr});"),
+            Stmt::Const { name, initializer } => format!("(const {name} = {initializer});"),
             Stmt::Var { name, initializer } => format!(
                 "(var {name} = {});",
                 if let Some(initializer) = initializer {
@@ -0,0 +1,143 @@
+use super::types::{ExprId, ExprNode, ExprRef, Stmt};
+
+/// Receives every statement and expression visited by [walk]. Both methods do nothing by
+/// default, so implementors only override the ones they need.
+pub trait Visitor {
+    fn visit_stmt(&mut self, _stmt: &Stmt) {}
+
+    fn visit_expr(&mut self, _expr: ExprRef<'_>) {}
+}
+
+/// Walks `statements` in pre-order, visiting each node before its children and children in
+/// source order, for tooling that counts or collects things across a whole program.
+pub fn walk<V: Visitor>(statements: &[Stmt], visitor: &mut V) {
+    for stmt in statements {
+        walk_stmt(stmt, visitor);
+    }
+}
+
+fn walk_stmt<V: Visitor>(stmt: &Stmt, visitor: &mut V) {
+    visitor.visit_stmt(stmt);
+
+    match stmt {
+        Stmt::Block(statements) => walk(statements, visitor),
+        Stmt::Break { .. } => {}
+        Stmt::Const { initializer, .. } => walk_expr(initializer.root(), visitor),
+        Stmt::Expression(expr) | Stmt::Print(expr) => walk_expr(expr.root(), visitor),
+        Stmt::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            walk_expr(condition.root(), visitor);
+            walk_stmt(then_branch, visitor);
+            if let Some(else_branch) = else_branch {
+                walk_stmt(else_branch, visitor);
+            }
+        }
+        Stmt::Labeled { body, .. } => walk_stmt(body, visitor),
+        Stmt::Var { initializer, .. } => {
+            if let Some(initializer) = initializer {
+                walk_expr(initializer.root(), visitor);
+            }
+        }
+        Stmt::While { condition, body } => {
+            walk_expr(condition.root(), visitor);
+            walk_stmt(body, visitor);
+        }
+    }
+}
+
+fn walk_expr<V: Visitor>(expr: ExprRef<'_>, visitor: &mut V) {
+    visitor.visit_expr(expr);
+
+    let mut walk_child = |id: &ExprId| walk_expr(expr.child(*id), visitor);
+
+    match expr.node() {
+        ExprNode::Assign { value, .. } => walk_child(value),
+        ExprNode::Binary { left, right, .. } | ExprNode::Logical { left, right, .. } => {
+            walk_child(left);
+            walk_child(right);
+        }
+        ExprNode::Call {
+            callee, arguments, ..
+        } => {
+            walk_child(callee);
+            arguments.iter().for_each(walk_child);
+        }
+        ExprNode::Index { object, index, .. } => {
+            walk_child(object);
+            walk_child(index);
+        }
+        ExprNode::Grouping { inner } => walk_child(inner),
+        ExprNode::Literal { .. } | ExprNode::Variable { .. } => {}
+        ExprNode::Unary { right, .. } => walk_child(right),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexer::token::TokenType;
+
+    use super::*;
+
+    /// Collects the name of every variable that is read or assigned.
+    #[derive(Default)]
+    struct IdentifierCollector(Vec<String>);
+
+    impl Visitor for IdentifierCollector {
+        fn visit_expr(&mut self, expr: ExprRef<'_>) {
+            if let ExprNode::Assign { name, .. } | ExprNode::Variable { name } = expr.node() {
+                if let TokenType::Identifier(ref name) = name.token_type {
+                    self.0.push(name.clone());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn walks_every_expression_in_order() {
+        let statements = crate::parse(
+            "var a = b; while (a < limit) { a = a + step; print upper(name); } if (done) print x; else print y;",
+        )
+        .unwrap();
+
+        let mut collector = IdentifierCollector::default();
+        walk(&statements, &mut collector);
+
+        let expected = [
+            "b", "a", "limit", "a", "a", "step", "upper", "name", "done", "x", "y",
+        ];
+        assert_eq!(collector.0, expected);
+    }
+
+    #[test]
+    fn visits_statements_before_their_children() {
+        #[derive(Default)]
+        struct Kinds(Vec<&'static str>);
+
+        impl Visitor for Kinds {
+            fn visit_stmt(&mut self, stmt: &Stmt) {
+                self.0.push(stmt.name());
+            }
+
+            fn visit_expr(&mut self, _expr: ExprRef<'_>) {
+                self.0.push("expression");
+            }
+        }
+
+        let statements = crate::parse("outer: while (true) { break outer; }").unwrap();
+
+        let mut kinds = Kinds::default();
+        walk(&statements, &mut kinds);
+
+        let expected = [
+            "labeled statement",
+            "while loop",
+            "expression",
+            "block",
+            "break statement",
+        ];
+        assert_eq!(kinds.0, expected);
+    }
+}
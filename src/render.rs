@@ -0,0 +1,132 @@
+use std::{
+    env,
+    io::{self, IsTerminal},
+};
+
+use crate::error::{Diagnostic, RloxError, Severity};
+
+const POSITION_STYLE: &str = "\x1b[1;36m";
+const MESSAGE_STYLE: &str = "\x1b[31m";
+const WARNING_STYLE: &str = "\x1b[33m";
+const RESET_STYLE: &str = "\x1b[0m";
+
+/// Renders errors for display in a terminal, emitting ANSI colors only when enabled.
+#[derive(Debug, Clone, Copy)]
+pub struct ErrorRenderer {
+    color: bool,
+}
+
+impl ErrorRenderer {
+    pub fn new(color: bool) -> Self {
+        Self { color }
+    }
+
+    /// Creates a renderer that only uses colors when stderr is a terminal and the `NO_COLOR`
+    /// environment variable is not set.
+    pub fn for_stderr() -> Self {
+        let color = io::stderr().is_terminal() && env::var_os("NO_COLOR").is_none();
+
+        Self::new(color)
+    }
+
+    pub fn render(&self, error: &RloxError) -> String {
+        if !self.color {
+            return error.to_string();
+        }
+
+        let position = match error.position() {
+            Some((line, col)) => format!("{POSITION_STYLE}[line {line}: col {col}]{RESET_STYLE} "),
+            None => "".to_owned(),
+        };
+
+        format!("{position}{MESSAGE_STYLE}{}{RESET_STYLE}", error.message())
+    }
+
+    /// Renders `diagnostic` like [ErrorRenderer::render], marking warnings with a `warning:`
+    /// prefix on the message.
+    pub fn render_diagnostic(&self, diagnostic: &Diagnostic) -> String {
+        let (label, message_style) = match diagnostic.severity {
+            Severity::Error => ("", MESSAGE_STYLE),
+            Severity::Warning => ("warning: ", WARNING_STYLE),
+        };
+        let (position_style, message_style, reset_style) = if self.color {
+            (POSITION_STYLE, message_style, RESET_STYLE)
+        } else {
+            ("", "", "")
+        };
+
+        let position = match diagnostic.position {
+            Some((line, col)) => format!("{position_style}[line {line}: col {col}]{reset_style} "),
+            None => "".to_owned(),
+        };
+
+        format!(
+            "{position}{message_style}{label}{}{reset_style}",
+            diagnostic.message
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::Stage, lexer::LexerError};
+
+    use super::*;
+
+    fn error() -> RloxError {
+        RloxError::Lexer(LexerError::UnexpectedCharacter {
+            character: '@',
+            line: 1,
+            col: 7,
+        })
+    }
+
+    #[test]
+    fn renders_plain_string_without_color() {
+        let renderer = ErrorRenderer::new(false);
+
+        assert_eq!(
+            renderer.render(&error()),
+            "[line 1: col 7] UnexpectedCharacter: @"
+        );
+    }
+
+    #[test]
+    fn renders_ansi_codes_with_color() {
+        let renderer = ErrorRenderer::new(true);
+
+        assert_eq!(
+            renderer.render(&error()),
+            "\x1b[1;36m[line 1: col 7]\x1b[0m \x1b[31mUnexpectedCharacter: @\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn renders_warning_diagnostics_with_label() {
+        let warning = Diagnostic {
+            stage: Stage::Resolver,
+            severity: Severity::Warning,
+            message: "expression result is unused".to_owned(),
+            position: Some((2, 3)),
+        };
+
+        assert_eq!(
+            ErrorRenderer::new(false).render_diagnostic(&warning),
+            "[line 2: col 3] warning: expression result is unused"
+        );
+        assert_eq!(
+            ErrorRenderer::new(true).render_diagnostic(&warning),
+            "\x1b[1;36m[line 2: col 3]\x1b[0m \x1b[33mwarning: expression result is unused\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn renders_error_diagnostics_like_errors() {
+        let diagnostic = Diagnostic::from(&error());
+
+        assert_eq!(
+            ErrorRenderer::new(false).render_diagnostic(&diagnostic),
+            ErrorRenderer::new(false).render(&error())
+        );
+    }
+}
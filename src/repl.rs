@@ -0,0 +1,251 @@
+use crate::{
+    error::RloxError,
+    interpreter::Interpreter,
+    lexer::{token::TokenType, LexerError},
+    parser::{error::ParserError, types::Stmt, Parser},
+};
+
+/// The result of feeding a line of input to a [ReplBuffer].
+#[derive(Debug, PartialEq)]
+pub enum ReplInput {
+    /// The buffered lines form a complete program.
+    Complete(Vec<Stmt>),
+    /// The buffered lines end in the middle of a statement, so more lines are needed.
+    Incomplete,
+    /// The buffered lines contain an error that more input cannot fix.
+    Invalid(RloxError),
+}
+
+/// Accumulates lines of REPL input until they can be parsed into complete statements, so that
+/// statements like blocks can span multiple lines.
+#[derive(Debug, Default)]
+pub struct ReplBuffer {
+    source: String,
+}
+
+impl ReplBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if no lines are buffered for an unfinished statement.
+    pub fn is_empty(&self) -> bool {
+        self.source.is_empty()
+    }
+
+    /// Discards any lines buffered for an unfinished statement.
+    pub fn clear(&mut self) {
+        self.source.clear();
+    }
+
+    /// Appends `line` to the buffer and tries to parse everything buffered so far. The buffer is
+    /// cleared unless the result is [ReplInput::Incomplete]. A final expression does not need a
+    /// trailing semicolon.
+    pub fn push_line(&mut self, line: &str) -> ReplInput {
+        self.source.push_str(line);
+        self.source.push('\n');
+
+        let input = match parse_lenient(&self.source) {
+            Ok(statements) => ReplInput::Complete(statements),
+            Err(e) if is_incomplete(&self.source, &e) => return ReplInput::Incomplete,
+            Err(e) => ReplInput::Invalid(e),
+        };
+
+        self.source.clear();
+
+        input
+    }
+}
+
+/// A command entered in the REPL that starts with `:` and is handled by the REPL itself instead
+/// of being interpreted as Lox source.
+#[derive(Debug, PartialEq)]
+pub enum MetaCommand {
+    /// `:help`, lists the available commands
+    Help,
+    /// `:env`, lists the variables currently defined
+    Env,
+    /// `:clear`, discards all variables and any unfinished statement
+    Clear,
+    /// `:quit`, exits the REPL
+    Quit,
+    Unknown(String),
+}
+
+impl MetaCommand {
+    /// Parses `line` as a meta-command. Returns `None` if `line` does not start with `:`, as it
+    /// is then Lox source.
+    pub fn parse(line: &str) -> Option<Self> {
+        let name = line.trim().strip_prefix(':')?;
+
+        let command = match name {
+            "help" => MetaCommand::Help,
+            "env" => MetaCommand::Env,
+            "clear" => MetaCommand::Clear,
+            "quit" => MetaCommand::Quit,
+            _ => MetaCommand::Unknown(name.to_owned()),
+        };
+
+        Some(command)
+    }
+
+    /// Runs the command against `interpreter` and `buffer`, returning the text to show the user.
+    /// [MetaCommand::Quit] does nothing here, exiting is left to the caller.
+    pub fn run(&self, interpreter: &mut Interpreter, buffer: &mut ReplBuffer) -> String {
+        match self {
+            MetaCommand::Help => concat!(
+                ":help   show this message\n",
+                ":env    list the variables currently defined\n",
+                ":clear  discard all variables and any unfinished statement\n",
+                ":quit   exit the REPL\n",
+            )
+            .to_owned(),
+            MetaCommand::Env => interpreter.dump_environment(),
+            MetaCommand::Clear => {
+                interpreter.reset();
+                buffer.clear();
+                String::new()
+            }
+            MetaCommand::Quit => String::new(),
+            MetaCommand::Unknown(name) => {
+                format!("unknown command ':{name}', type ':help' for a list of commands\n")
+            }
+        }
+    }
+}
+
+/// Like [crate::parse], but with the parser in lenient mode.
+fn parse_lenient(source: &str) -> Result<Vec<Stmt>, RloxError> {
+    let mut parser = Parser::new(crate::lex(source)?);
+    parser.set_lenient(true);
+
+    Ok(parser.parse()?)
+}
+
+/// Checks if `error` was caused by `source` ending before a statement is finished.
+fn is_incomplete(source: &str, error: &RloxError) -> bool {
+    match error {
+        RloxError::Lexer(LexerError::UnterminatedString { .. }) => true,
+        RloxError::Parser(ParserError::UnexpectedEndOfTokens) => true,
+        RloxError::Parser(ParserError::MissingExpectedToken { .. }) => {
+            has_unclosed_delimiters(source)
+        }
+        _ => false,
+    }
+}
+
+/// Checks if `source` has more opening braces or parentheses than closing ones.
+fn has_unclosed_delimiters(source: &str) -> bool {
+    let Ok(tokens) = crate::lex(source) else {
+        return false;
+    };
+
+    let (braces, parens) =
+        tokens
+            .iter()
+            .fold((0, 0), |(braces, parens), token| match token.token_type {
+                TokenType::LeftBrace => (braces + 1, parens),
+                TokenType::RightBrace => (braces - 1, parens),
+                TokenType::LeftParen => (braces, parens + 1),
+                TokenType::RightParen => (braces, parens - 1),
+                _ => (braces, parens),
+            });
+
+    braces > 0 || parens > 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_empty_and_whitespace_only_lines() {
+        let mut buffer = ReplBuffer::new();
+
+        assert_eq!(buffer.push_line(""), ReplInput::Complete(Vec::new()));
+        assert_eq!(buffer.push_line(" \t "), ReplInput::Complete(Vec::new()));
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn buffers_lines_until_block_is_closed() {
+        let mut buffer = ReplBuffer::new();
+
+        assert_eq!(buffer.push_line("if (true) {"), ReplInput::Incomplete);
+        assert_eq!(buffer.push_line("  print 1;"), ReplInput::Incomplete);
+        assert!(!buffer.is_empty());
+
+        let ReplInput::Complete(statements) = buffer.push_line("}") else {
+            panic!("expected a complete statement");
+        };
+        assert_eq!(statements.len(), 1);
+        assert_eq!(statements[0].name(), "if statement");
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn buffers_lines_until_string_is_terminated() {
+        let mut buffer = ReplBuffer::new();
+
+        assert_eq!(buffer.push_line("print \"hello"), ReplInput::Incomplete);
+        assert!(matches!(
+            buffer.push_line("world\";"),
+            ReplInput::Complete(_)
+        ));
+    }
+
+    #[test]
+    fn reports_errors_that_more_input_cannot_fix() {
+        let mut buffer = ReplBuffer::new();
+
+        let expected = ReplInput::Invalid(RloxError::Parser(ParserError::MissingExpectedToken {
+            token_type: TokenType::Semicolon,
+            message: "expected ';' after value".to_owned(),
+            line: 1,
+            col: 7,
+        }));
+
+        assert_eq!(buffer.push_line("print 1"), expected);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn accepts_final_expression_without_semicolon() {
+        let mut buffer = ReplBuffer::new();
+
+        let ReplInput::Complete(statements) = buffer.push_line("var a = 1; a + 2") else {
+            panic!("expected complete statements");
+        };
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[1].name(), "expression statement");
+    }
+
+    #[test]
+    fn parses_only_lines_starting_with_colon_as_meta_commands() {
+        assert_eq!(MetaCommand::parse(" :env "), Some(MetaCommand::Env));
+        assert_eq!(
+            MetaCommand::parse(":nope"),
+            Some(MetaCommand::Unknown("nope".to_owned()))
+        );
+        assert_eq!(MetaCommand::parse("print 1;"), None);
+    }
+
+    #[test]
+    fn env_and_clear_meta_commands() {
+        let mut interpreter = Interpreter::new();
+        let mut buffer = ReplBuffer::new();
+        interpreter.interpret(crate::parse("var a = 1;").unwrap());
+        buffer.push_line("{");
+
+        let env = MetaCommand::Env.run(&mut interpreter, &mut buffer);
+        assert_eq!(env, "scope 0 (global):\n  a = 1\n");
+
+        let clear = MetaCommand::Clear.run(&mut interpreter, &mut buffer);
+        assert_eq!(clear, "");
+        assert!(buffer.is_empty());
+        assert_eq!(
+            MetaCommand::Env.run(&mut interpreter, &mut buffer),
+            "scope 0 (global):\n"
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use thiserror::Error;
+
+/// A problem found by the [Resolver](super::Resolver). These are warnings, so the program can
+/// still run.
+#[derive(Debug, Clone, Error, PartialEq)]
+pub enum ResolverError {
+    #[error("'{}' shadows the native function with the same name", .name)]
+    ShadowsBuiltin {
+        name: String,
+        /// Line of the declaration
+        line: usize,
+        /// Column of the declaration
+        col: usize,
+    },
+
+    #[error("expression result is unused")]
+    UnusedExpressionResult {
+        /// Line of the expression's first operator or variable
+        line: usize,
+        /// Column of the expression's first operator or variable
+        col: usize,
+    },
+}
+
+impl ResolverError {
+    /// Returns the line and column in the source code where the problem was found.
+    pub fn position(&self) -> Option<(usize, usize)> {
+        match self {
+            ResolverError::ShadowsBuiltin { line, col, .. }
+            | ResolverError::UnusedExpressionResult { line, col } => Some((*line, *col)),
+        }
+    }
+}
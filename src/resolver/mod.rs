@@ -0,0 +1,146 @@
+use std::collections::HashSet;
+
+use crate::{
+    interpreter::natives,
+    lexer::token::{Token, TokenType},
+    parser::types::{ExprId, ExprNode, ExprRef, Stmt},
+};
+
+use self::error::ResolverError;
+
+pub mod error;
+
+/// Walks parsed statements before they are executed to find likely mistakes
+pub struct Resolver {
+    /// Names of the native functions that declarations could shadow
+    natives: HashSet<&'static str>,
+    /// Number of scopes enclosing the statement currently being resolved
+    depth: usize,
+    /// Whether a final top-level expression statement is exempt from unused result warnings
+    allow_final_expression: bool,
+    warnings: Vec<ResolverError>,
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Self {
+            natives: natives::all().iter().map(|native| native.name).collect(),
+            depth: 0,
+            allow_final_expression: false,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Allows or disallows an unused expression as the last top-level statement. Allow it when
+    /// the value of the last statement is used as the result of the program, like in a REPL.
+    pub fn set_allow_final_expression(&mut self, allow: bool) {
+        self.allow_final_expression = allow;
+    }
+
+    /// Resolves `statements`, returning every warning found.
+    pub fn resolve(mut self, statements: &[Stmt]) -> Vec<ResolverError> {
+        // An expression statement can only be warned about for its unused result, so the final
+        // one can be skipped entirely
+        let statements = match statements {
+            [rest @ .., Stmt::Expression(_)] if self.allow_final_expression => rest,
+            _ => statements,
+        };
+
+        statements.iter().for_each(|stmt| self.resolve_stmt(stmt));
+
+        self.warnings
+    }
+
+    fn resolve_stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Block(statements) => {
+                self.depth += 1;
+                statements.iter().for_each(|stmt| self.resolve_stmt(stmt));
+                self.depth -= 1;
+            }
+            Stmt::Const { name, .. } | Stmt::Var { name, .. } => self.declare(name),
+            Stmt::If {
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                self.resolve_stmt(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.resolve_stmt(else_branch);
+                }
+            }
+            Stmt::Labeled { body, .. } | Stmt::While { body, .. } => self.resolve_stmt(body),
+            Stmt::Expression(expr) => self.check_unused(expr.root()),
+            Stmt::Break { .. } | Stmt::Print(_) => {}
+        }
+    }
+
+    /// Warns if `expr` is used as a statement even though evaluating it has no effect.
+    fn check_unused(&mut self, expr: ExprRef<'_>) {
+        if !is_pure(expr) {
+            return;
+        }
+
+        // Literals carry no position, so an expression made of nothing but literals is not
+        // reported
+        if let Some((line, col)) = position(expr) {
+            self.warnings
+                .push(ResolverError::UnusedExpressionResult { line, col });
+        }
+    }
+
+    /// Warns if a global declaration of `name` shadows a native function.
+    fn declare(&mut self, name: &Token) {
+        let TokenType::Identifier(ref identifier) = name.token_type else {
+            return;
+        };
+
+        if self.depth == 0 && self.natives.contains(identifier.as_str()) {
+            self.warnings.push(ResolverError::ShadowsBuiltin {
+                name: identifier.clone(),
+                line: name.line,
+                col: name.col,
+            });
+        }
+    }
+}
+
+/// Returns whether evaluating `expr` has no effect other than producing a value. Calls and
+/// assignments are never pure.
+fn is_pure(expr: ExprRef<'_>) -> bool {
+    let is_pure = |id: &ExprId| is_pure(expr.child(*id));
+
+    match expr.node() {
+        ExprNode::Assign { .. } | ExprNode::Call { .. } => false,
+        ExprNode::Binary { left, right, .. } | ExprNode::Logical { left, right, .. } => {
+            is_pure(left) && is_pure(right)
+        }
+        ExprNode::Index { object, index, .. } => is_pure(object) && is_pure(index),
+        ExprNode::Grouping { inner } => is_pure(inner),
+        ExprNode::Unary { right, .. } => is_pure(right),
+        ExprNode::Literal { .. } | ExprNode::Variable { .. } => true,
+    }
+}
+
+/// Returns the position of the first operator or variable in `expr`, if there is one.
+fn position(expr: ExprRef<'_>) -> Option<(usize, usize)> {
+    match expr.node() {
+        ExprNode::Binary { operator, .. }
+        | ExprNode::Logical { operator, .. }
+        | ExprNode::Unary { operator, .. } => Some((operator.src_line, operator.src_col)),
+        ExprNode::Assign { name, .. } | ExprNode::Variable { name } => Some((name.line, name.col)),
+        ExprNode::Call { paren, .. } => Some((paren.line, paren.col)),
+        ExprNode::Index { bracket, .. } => Some((bracket.line, bracket.col)),
+        ExprNode::Grouping { inner } => position(expr.child(*inner)),
+        ExprNode::Literal { .. } => None,
+    }
+}
+
+#[cfg(test)]
+mod tests;
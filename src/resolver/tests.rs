@@ -0,0 +1,70 @@
+use pretty_assertions::assert_eq;
+
+use super::{error::ResolverError, Resolver};
+
+#[test]
+fn warn_on_global_declaration_shadowing_native() {
+    let statements = crate::parse("var pow = 1; const concat = 2;").unwrap();
+
+    let warnings = Resolver::new().resolve(&statements);
+    let expected = [
+        ResolverError::ShadowsBuiltin {
+            name: "pow".to_owned(),
+            line: 1,
+            col: 7,
+        },
+        ResolverError::ShadowsBuiltin {
+            name: "concat".to_owned(),
+            line: 1,
+            col: 25,
+        },
+    ];
+
+    assert_eq!(warnings, expected);
+}
+
+#[test]
+fn no_warning_on_local_declaration_shadowing_native() {
+    let statements = crate::parse("{ var pow = 1; } var power = 2;").unwrap();
+
+    let warnings = Resolver::new().resolve(&statements);
+
+    assert_eq!(warnings, []);
+}
+
+#[test]
+fn warn_on_unused_pure_expression() {
+    let statements = crate::parse("var x; 1 + 2; -x; (x);").unwrap();
+
+    let warnings = Resolver::new().resolve(&statements);
+    let expected = [
+        ResolverError::UnusedExpressionResult { line: 1, col: 10 },
+        ResolverError::UnusedExpressionResult { line: 1, col: 15 },
+        ResolverError::UnusedExpressionResult { line: 1, col: 20 },
+    ];
+
+    assert_eq!(warnings, expected);
+}
+
+#[test]
+fn no_warning_on_calls_and_assignments() {
+    let statements = crate::parse("var x; f(); x = 1; 1 + f();").unwrap();
+
+    let warnings = Resolver::new().resolve(&statements);
+
+    assert_eq!(warnings, []);
+}
+
+#[test]
+fn final_expression_can_be_allowed() {
+    let statements = crate::parse("var x; x; x;").unwrap();
+
+    let mut resolver = Resolver::new();
+    resolver.set_allow_final_expression(true);
+    let warnings = resolver.resolve(&statements);
+
+    assert_eq!(
+        warnings,
+        [ResolverError::UnusedExpressionResult { line: 1, col: 8 }]
+    );
+}
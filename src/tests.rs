@@ -0,0 +1,305 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    error::{Diagnostic, RloxError, RloxErrors, Severity, Stage},
+    interpreter::{error::RuntimeError, Interpreter},
+    lexer::{
+        token::{Token, TokenType},
+        LexerError,
+    },
+    parser::{
+        error::ParserError,
+        types::{Expr, Operator, OperatorType, Stmt, Value},
+    },
+};
+
+#[test]
+fn can_lex_small_program() {
+    let tokens = crate::lex("var a = 1;").unwrap();
+    let expected = [
+        Token {
+            token_type: TokenType::Var,
+            line: 1,
+            col: 3,
+        },
+        Token {
+            token_type: TokenType::Identifier("a".to_owned()),
+            line: 1,
+            col: 5,
+        },
+        Token {
+            token_type: TokenType::Equal,
+            line: 1,
+            col: 7,
+        },
+        Token {
+            token_type: TokenType::Number(1.0),
+            line: 1,
+            col: 9,
+        },
+        Token {
+            token_type: TokenType::Semicolon,
+            line: 1,
+            col: 10,
+        },
+    ];
+
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn lex_returns_first_error() {
+    let result = crate::lex("var a = @;");
+    let expected = Err(LexerError::UnexpectedCharacter {
+        character: '@',
+        line: 1,
+        col: 9,
+    });
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn can_parse_small_program() {
+    let statements = crate::parse("var a = 1; print a;").unwrap();
+    let expected = [
+        Stmt::Var {
+            name: Token {
+                token_type: TokenType::Identifier("a".to_owned()),
+                line: 1,
+                col: 5,
+            },
+            initializer: Some(Expr::literal(Value::Number(1.0))),
+        },
+        Stmt::Print(Expr::variable(Token {
+            token_type: TokenType::Identifier("a".to_owned()),
+            line: 1,
+            col: 18,
+        })),
+    ];
+
+    assert_eq!(statements, expected);
+}
+
+#[test]
+fn parse_returns_lexer_and_parser_errors() {
+    let lexer_error = crate::parse("print @;");
+    let expected = Err(RloxError::Lexer(LexerError::UnexpectedCharacter {
+        character: '@',
+        line: 1,
+        col: 7,
+    }));
+    assert_eq!(lexer_error, expected);
+
+    let parser_error = crate::parse("print 1");
+    let expected = Err(RloxError::Parser(ParserError::MissingExpectedToken {
+        token_type: TokenType::Semicolon,
+        message: "expected ';' after value".to_owned(),
+        line: 1,
+        col: 7,
+    }));
+    assert_eq!(parser_error, expected);
+}
+
+#[test]
+fn run_returns_runtime_errors() {
+    let mut interpreter = Interpreter::new();
+
+    let result = crate::run("var a = 1;\nprint b;", &mut interpreter);
+    let expected = Err(RloxError::Runtime(RuntimeError::UndefinedVariable {
+        name: "b".to_owned(),
+        line: 2,
+        col: 7,
+    }));
+
+    assert_eq!(result, expected);
+}
+
+#[test]
+fn declarations_in_if_and_loop_bodies_do_not_leak() {
+    let sources = [
+        "if (true) { var x = 1; }\nprint x;",
+        "var i = 0; while (i < 1) { var x = 1; i = i + 1; }\nprint x;",
+        "for (var i = 0; i < 1; i = i + 1) { var x = 1; }\nprint x;",
+    ];
+
+    for source in sources {
+        let mut interpreter = Interpreter::new();
+
+        let result = crate::run(source, &mut interpreter);
+        let expected = Err(RloxError::Runtime(RuntimeError::UndefinedVariable {
+            name: "x".to_owned(),
+            line: 2,
+            col: 7,
+        }));
+
+        assert_eq!(result, expected, "{source}");
+    }
+}
+
+#[test]
+fn lexer_error_converts_and_renders_with_position() {
+    let error = RloxError::from(LexerError::UnexpectedCharacter {
+        character: '@',
+        line: 1,
+        col: 7,
+    });
+
+    assert_eq!(error.to_string(), "[line 1: col 7] UnexpectedCharacter: @");
+}
+
+#[test]
+fn parser_error_converts_and_renders_with_position() {
+    let error = RloxError::from(ParserError::InvalidAssignmentTarget(Token {
+        token_type: TokenType::Equal,
+        line: 2,
+        col: 5,
+    }));
+
+    assert_eq!(
+        error.to_string(),
+        "[line 2: col 5] invalid assignment target"
+    );
+}
+
+#[test]
+fn runtime_error_converts_and_renders_with_position() {
+    let error = RloxError::from(RuntimeError::InvalidOperands {
+        operator: Operator {
+            operator_type: OperatorType::Minus,
+            src_line: 3,
+            src_col: 9,
+        },
+        expected: "two numbers".to_owned(),
+    });
+
+    assert_eq!(
+        error.to_string(),
+        "[line 3: col 9] invalid operands for '-', expected two numbers"
+    );
+}
+
+#[test]
+fn run_timed_executes_program() {
+    let mut interpreter = Interpreter::new();
+
+    crate::run_timed("var a = 1 + 2;", &mut interpreter).unwrap();
+
+    // `a` was defined by the timed run
+    assert!(crate::run("a;", &mut interpreter).is_ok());
+}
+
+#[test]
+fn run_timed_reports_every_lexer_error() {
+    let mut interpreter = Interpreter::new();
+
+    let result = crate::run_timed("print 1 @ 2 #;", &mut interpreter);
+    let expected = vec![
+        RloxError::Lexer(LexerError::UnexpectedCharacter {
+            character: '@',
+            line: 1,
+            col: 9,
+        }),
+        RloxError::Lexer(LexerError::UnexpectedCharacter {
+            character: '#',
+            line: 1,
+            col: 13,
+        }),
+    ];
+
+    assert_eq!(result, Err(RloxErrors(expected)));
+}
+
+#[test]
+fn rlox_errors_render_each_error_with_its_position() {
+    let errors = RloxErrors(vec![
+        RloxError::from(ParserError::BreakOutsideLoop(Token {
+            token_type: TokenType::Break,
+            line: 1,
+            col: 5,
+        })),
+        RloxError::from(RuntimeError::StepLimitExceeded { limit: 10 }),
+    ]);
+
+    assert_eq!(
+        errors.to_string(),
+        "[line 1: col 5] cannot use 'break' outside of a loop\nexceeded the limit of 10 execution steps"
+    );
+}
+
+#[test]
+fn run_diagnostics_collects_parser_and_runtime_errors() {
+    let (value, diagnostics) = crate::run_diagnostics("var = 1;\nvar a = -nil;\n1 + 2;");
+    let expected = [
+        Diagnostic {
+            stage: Stage::Parser,
+            severity: Severity::Error,
+            message: "expected Identifier: expected variable name".to_owned(),
+            position: Some((1, 5)),
+        },
+        Diagnostic {
+            stage: Stage::Runtime,
+            severity: Severity::Error,
+            message: "invalid operator '-' for value nil".to_owned(),
+            position: Some((2, 9)),
+        },
+    ];
+
+    assert_eq!(value, Some(Value::Number(3.0)));
+    assert_eq!(diagnostics, expected);
+}
+
+#[test]
+fn run_diagnostics_collects_all_lexer_errors() {
+    let (value, diagnostics) = crate::run_diagnostics("print @ + #;");
+
+    let stages: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.stage)
+        .collect();
+
+    assert_eq!(value, None);
+    assert_eq!(stages, [Stage::Lexer, Stage::Lexer]);
+}
+
+#[test]
+fn run_diagnostics_includes_resolver_warnings() {
+    let (value, diagnostics) = crate::run_diagnostics("var pow = 1; pow;");
+    let expected = [Diagnostic {
+        stage: Stage::Resolver,
+        severity: Severity::Warning,
+        message: "'pow' shadows the native function with the same name".to_owned(),
+        position: Some((1, 7)),
+    }];
+
+    assert_eq!(value, Some(Value::Number(1.0)));
+    assert_eq!(diagnostics, expected);
+}
+
+#[test]
+fn empty_and_whitespace_only_sources_run_without_errors() {
+    for source in ["", "   ", "\n\t\r\n", "// only a comment\n"] {
+        let mut interpreter = Interpreter::new();
+
+        assert_eq!(crate::parse(source), Ok(Vec::new()), "source: {source:?}");
+        assert_eq!(
+            crate::run(source, &mut interpreter),
+            Ok(()),
+            "source: {source:?}"
+        );
+        assert_eq!(crate::run_diagnostics(source), (None, Vec::new()));
+    }
+}
+
+#[test]
+fn run_diagnostics_includes_lexer_warnings() {
+    let (value, diagnostics) = crate::run_diagnostics("9007199254740993;");
+
+    let [diagnostic] = diagnostics.as_slice() else {
+        panic!("expected exactly one diagnostic, got {diagnostics:?}");
+    };
+    assert_eq!(value, Some(Value::Number(9007199254740992.0)));
+    assert_eq!(diagnostic.stage, Stage::Lexer);
+    assert_eq!(diagnostic.severity, Severity::Warning);
+    assert_eq!(diagnostic.position, Some((1, 1)));
+}
@@ -0,0 +1,109 @@
+use std::{
+    env, fs, process,
+    process::{Command, Output},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Runs the rlox binary with `args` followed by the path to a script containing `source`.
+fn run_cli(args: &[&str], source: &str) -> Output {
+    // Tests run concurrently, possibly in several processes, so every script gets its own file
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let path = env::temp_dir().join(format!("rlox_cli_{}_{id}.lox", process::id()));
+    fs::write(&path, source).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rlox"))
+        .args(args)
+        .arg(&path)
+        .output();
+    fs::remove_file(&path).unwrap();
+
+    output.unwrap()
+}
+
+#[test]
+fn check_reports_syntax_errors_without_running() {
+    let output = run_cli(
+        &["--no-color", "--check"],
+        "print \"side effect\";\nvar = 1;\n",
+    );
+
+    assert_eq!(output.status.code(), Some(65));
+    assert!(output.stdout.is_empty());
+    assert!(!output.stderr.is_empty());
+}
+
+#[test]
+fn check_accepts_valid_script() {
+    let output = run_cli(&["--no-color", "--check"], "print \"side effect\";\n");
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn emit_json_prints_ast_without_running() {
+    let output = run_cli(&["--no-color", "--emit-json"], "print \"side effect\";\n");
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success());
+    assert!(
+        stdout.starts_with(r#"[{"kind":"Print","#),
+        "stdout: {stdout}"
+    );
+}
+
+#[test]
+fn emit_json_reports_syntax_errors() {
+    let output = run_cli(&["--no-color", "--emit-json"], "var = 1;\n");
+
+    assert_eq!(output.status.code(), Some(65));
+    assert!(output.stdout.is_empty());
+    assert!(!output.stderr.is_empty());
+}
+
+#[test]
+fn reports_every_lexer_error() {
+    let output = run_cli(&["--no-color"], "print 1 @ 2 #;\n");
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(output.status.code(), Some(65));
+    assert!(output.stdout.is_empty());
+    assert_eq!(stderr.lines().count(), 2, "stderr: {stderr}");
+}
+
+#[test]
+fn rejects_time_with_check() {
+    let output = run_cli(&["--no-color", "--time", "--check"], "print 1;\n");
+
+    assert_eq!(output.status.code(), Some(64));
+}
+
+#[test]
+fn check_reports_warnings_without_failing() {
+    let output = run_cli(&["--no-color", "--check"], "var pow = 1;\n");
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(output.status.success());
+    assert!(
+        stderr.starts_with("[line 1: col 7] warning: 'pow' shadows"),
+        "stderr: {stderr}"
+    );
+}
+
+#[test]
+fn reports_warnings_before_running() {
+    let output = run_cli(&["--no-color"], "1 + 2;\nprint 9007199254740993;\n");
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "9007199254740992\n"
+    );
+    assert_eq!(stderr.lines().count(), 2, "stderr: {stderr}");
+    assert!(
+        stderr.lines().all(|line| line.contains("warning: ")),
+        "stderr: {stderr}"
+    );
+}